@@ -0,0 +1,300 @@
+use crate::decompress::decompress_reader;
+use crate::utils::is_csv_file;
+use flate2::read::DeflateDecoder;
+use std::fs::File;
+use std::io::{self, Read, Seek, SeekFrom};
+use std::path::{Component, Path, PathBuf};
+use zip::{CompressionMethod, ZipArchive};
+
+/// File name suffixes of tar archives, compressed or not.
+const TAR_SUFFIXES: [&str; 7] = [".tar", ".tgz", ".tar.gz", ".tar.zst", ".tar.bz2", ".tar.xz", ".tbz2"];
+
+/// Archive formats whose CSV members can be converted without extracting them.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum ArchiveKind {
+    Zip,
+    Tar,
+}
+
+/// Returns the kind of archive and the length of its suffix, e.g. 7 for `.tar.gz`. Suffixes are matched
+/// case-insensitively.
+fn archive_kind(path: &Path) -> Option<(ArchiveKind, usize)> {
+    let name = path.file_name()?.to_str()?.to_ascii_lowercase();
+    if name.ends_with(".zip") {
+        return Some((ArchiveKind::Zip, ".zip".len()));
+    }
+    TAR_SUFFIXES
+        .iter()
+        .filter(|suffix| name.ends_with(*suffix))
+        .map(|suffix| (ArchiveKind::Tar, suffix.len()))
+        .max_by_key(|(_, len)| *len)
+}
+
+/// Returns `true` if the path names a zip or tar archive, e.g. `drop.zip` or `drop.tar.gz`.
+pub(crate) fn is_archive(path: &Path) -> bool {
+    archive_kind(path).is_some()
+}
+
+/// Returns `true` if the path names a tar archive, compressed or not.
+pub(crate) fn is_tar(path: &Path) -> bool {
+    matches!(archive_kind(path), Some((ArchiveKind::Tar, _)))
+}
+
+/// Returns the path of an archive without its suffix, e.g. `drops/2026-10` for `drops/2026-10.tar.gz`.
+pub(crate) fn archive_stem(path: &Path) -> PathBuf {
+    match (archive_kind(path), path.file_name().and_then(|n| n.to_str())) {
+        (Some((_, len)), Some(name)) => path.with_file_name(&name[..name.len() - len]),
+        _ => path.to_path_buf(),
+    }
+}
+
+/// Splits the path of an archive member, e.g. `drop.zip/sales/jan.csv`, into the archive and the member name.
+///
+/// Returns `None` if the path is not inside an archive.
+pub(crate) fn split_member(path: &Path) -> Option<(&Path, String)> {
+    if path.is_file() {
+        return None;
+    }
+    let archive = path.ancestors().skip(1).find(|a| a.is_file())?;
+    if !is_archive(archive) {
+        return None;
+    }
+
+    let member = path.strip_prefix(archive).ok()?;
+    let parts: Vec<&str> = member.components().map(|c| c.as_os_str().to_str()).collect::<Option<_>>()?;
+    Some((archive, parts.join("/")))
+}
+
+/// Lists the CSV members of an archive, in the order they are stored.
+///
+/// Members whose name would escape the output directory, e.g. `../data.csv`, are left out.
+pub(crate) fn list_members(path: &Path) -> io::Result<Vec<String>> {
+    let is_member = |name: &str| {
+        let member = Path::new(name);
+        member.components().all(|c| matches!(c, Component::Normal(_))) && is_csv_file(member)
+    };
+
+    let mut members = Vec::new();
+    match archive_kind(path) {
+        Some((ArchiveKind::Zip, _)) => {
+            let mut archive = ZipArchive::new(File::open(path)?)?;
+            for index in 0..archive.len() {
+                let file = archive.by_index_raw(index)?;
+                if file.is_file() && is_member(file.name()?.as_ref()) {
+                    members.push(file.name()?.into_owned());
+                }
+            }
+        }
+        Some((ArchiveKind::Tar, _)) => {
+            let mut archive = tar::Archive::new(decompress_reader(File::open(path)?, path)?);
+            for entry in archive.entries()? {
+                let entry = entry?;
+                let name = entry.path()?.to_string_lossy().into_owned();
+                if entry.header().entry_type().is_file() && is_member(&name) {
+                    members.push(name);
+                }
+            }
+        }
+        None => return Err(io::Error::new(io::ErrorKind::InvalidInput, "not a zip or tar archive")),
+    }
+    Ok(members)
+}
+
+/// Opens a member of an archive for streaming, without extracting it.
+///
+/// A tar archive has no index, so it is read up to the member. To read all members of a tar archive, use
+/// [`walk_tar`] instead.
+///
+/// # Arguments
+///
+/// * `path` - The path of the archive.
+/// * `member` - The name of the member, as returned by [`list_members`].
+pub(crate) fn open_member(path: &Path, member: &str) -> io::Result<Box<dyn Read>> {
+    let not_found = || io::Error::new(io::ErrorKind::NotFound, format!("no member '{}' in {}", member, path.display()));
+
+    match archive_kind(path) {
+        Some((ArchiveKind::Zip, _)) => {
+            let mut archive = ZipArchive::new(File::open(path)?)?;
+            let index = archive.index_for_name(member).ok_or_else(not_found)?;
+            let file = archive.by_index_raw(index)?;
+            if file.encrypted() {
+                return Err(io::Error::new(
+                    io::ErrorKind::Unsupported,
+                    format!("member '{}' is encrypted", member),
+                ));
+            }
+            let start = file.data_start().ok_or_else(|| io::Error::other("missing data offset"))?;
+            let (size, method) = (file.compressed_size(), file.compression());
+
+            let mut reader = File::open(path)?;
+            reader.seek(SeekFrom::Start(start))?;
+            let data = reader.take(size);
+            if method == CompressionMethod::DEFLATE {
+                Ok(Box::new(DeflateDecoder::new(data)))
+            } else if [
+                CompressionMethod::STORE,
+                CompressionMethod::BZIP2,
+                CompressionMethod::ZSTD,
+                CompressionMethod::XZ,
+            ]
+            .contains(&method)
+            {
+                // bzip2, zstd and xz data is decompressed by its magic bytes, like a compressed file
+                Ok(Box::new(data))
+            } else {
+                Err(io::Error::new(
+                    io::ErrorKind::Unsupported,
+                    format!("member '{}' uses the unsupported compression method {:?}", member, method),
+                ))
+            }
+        }
+        Some((ArchiveKind::Tar, _)) => {
+            let mut reader = CountingReader {
+                inner: decompress_reader(File::open(path)?, path)?,
+                count: 0,
+            };
+            let (position, size) = {
+                let mut archive = tar::Archive::new(&mut reader);
+                let mut found = None;
+                for entry in archive.entries()? {
+                    let entry = entry?;
+                    if entry.header().entry_type().is_file() && entry.path()?.to_string_lossy() == member {
+                        found = Some((entry.raw_file_position(), entry.size()));
+                        break;
+                    }
+                }
+                found.ok_or_else(not_found)?
+            };
+
+            // The tar reader stops right after the header of the member, skip whatever lies in between
+            let skip = position
+                .checked_sub(reader.count)
+                .ok_or_else(|| io::Error::other("tar reader read past the member"))?;
+            io::copy(&mut (&mut reader).take(skip), &mut io::sink())?;
+            Ok(Box::new(reader.take(size)))
+        }
+        None => Err(io::Error::new(io::ErrorKind::InvalidInput, "not a zip or tar archive")),
+    }
+}
+
+/// Reads a tar archive once and calls `f` with the name and the data of each regular file, in the order they are
+/// stored.
+///
+/// # Arguments
+///
+/// * `path` - The path of the archive.
+/// * `f` - Called for every file. The data it leaves unread is skipped.
+pub(crate) fn walk_tar(path: &Path, mut f: impl FnMut(&str, &mut dyn Read)) -> io::Result<()> {
+    let mut archive = tar::Archive::new(decompress_reader(File::open(path)?, path)?);
+    for entry in archive.entries()? {
+        let mut entry = entry?;
+        if entry.header().entry_type().is_file() {
+            let name = entry.path()?.to_string_lossy().into_owned();
+            f(&name, &mut entry);
+        }
+    }
+    Ok(())
+}
+
+/// A reader that counts the bytes read from it.
+struct CountingReader<R> {
+    inner: R,
+    count: u64,
+}
+
+impl<R: Read> Read for CountingReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let read = self.inner.read(buf)?;
+        self.count += read as u64;
+        Ok(read)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    #[test]
+    fn test_archive_names() {
+        assert!(is_archive(Path::new("drop.zip")));
+        assert!(is_archive(Path::new("drop.tar.gz")));
+        assert!(!is_archive(Path::new("drop.csv.gz")));
+        assert!(is_archive(Path::new("DROP.ZIP")));
+        assert!(is_tar(Path::new("Drop.Tar.GZ")));
+        assert!(!is_tar(Path::new("drop.zip")));
+
+        assert_eq!(archive_stem(Path::new("drops/2026-10.tar.gz")), PathBuf::from("drops/2026-10"));
+        assert_eq!(archive_stem(Path::new("drop.zip")), PathBuf::from("drop"));
+        assert_eq!(archive_stem(Path::new("Drops/2026-10.TGZ")), PathBuf::from("Drops/2026-10"));
+    }
+
+    #[test]
+    fn test_zip_members() {
+        let path = std::env::temp_dir().join("cc2p_test_zip_members.zip");
+        let mut writer = zip::ZipWriter::new(File::create(&path).unwrap());
+        let options = zip::write::SimpleFileOptions::default().compression_method(zip::CompressionMethod::Stored);
+        writer
+            .start_file("sales/jan.csv", options.compression_method(zip::CompressionMethod::Deflated))
+            .unwrap();
+        writer.write_all(b"id\n1\n").unwrap();
+        writer.start_file("readme.txt", options).unwrap();
+        writer.write_all(b"hello").unwrap();
+        writer.start_file("../evil.csv", options).unwrap();
+        writer.write_all(b"id\n2\n").unwrap();
+        writer.finish().unwrap();
+
+        assert_eq!(list_members(&path).unwrap(), vec!["sales/jan.csv"]);
+
+        let member_path = path.join("sales").join("jan.csv");
+        let (archive, member) = split_member(&member_path).unwrap();
+        assert_eq!(archive, path.as_path());
+
+        let mut content = String::new();
+        open_member(archive, &member).unwrap().read_to_string(&mut content).unwrap();
+        assert_eq!(content, "id\n1\n");
+        assert!(open_member(&path, "missing.csv").is_err());
+
+        let _ = std::fs::remove_file(path);
+    }
+
+    #[test]
+    fn test_tar_members() {
+        let path = std::env::temp_dir().join("cc2p_test_tar_members.tar.gz");
+        let encoder = flate2::write::GzEncoder::new(File::create(&path).unwrap(), flate2::Compression::default());
+        let mut builder = tar::Builder::new(encoder);
+        for (name, content) in [("a.csv", "id\n1\n"), ("notes.txt", "hello"), ("b.csv", "id\n2\n3\n")] {
+            let mut header = tar::Header::new_gnu();
+            header.set_size(content.len() as u64);
+            header.set_mode(0o644);
+            header.set_cksum();
+            builder.append_data(&mut header, name, content.as_bytes()).unwrap();
+        }
+        builder.into_inner().unwrap().finish().unwrap();
+
+        assert_eq!(list_members(&path).unwrap(), vec!["a.csv", "b.csv"]);
+
+        let mut content = String::new();
+        open_member(&path, "b.csv").unwrap().read_to_string(&mut content).unwrap();
+        assert_eq!(content, "id\n2\n3\n");
+
+        // Reading only the head of a member does not get in the way of the next one
+        let mut files = Vec::new();
+        walk_tar(&path, |name, data| {
+            let mut head = [0; 3];
+            data.read_exact(&mut head).unwrap();
+            files.push((name.to_string(), String::from_utf8_lossy(&head).into_owned()));
+        })
+        .unwrap();
+        assert_eq!(
+            files,
+            vec![
+                ("a.csv".into(), "id\n".into()),
+                ("notes.txt".into(), "hel".into()),
+                ("b.csv".into(), "id\n".into())
+            ]
+        );
+
+        let _ = std::fs::remove_file(path);
+    }
+}
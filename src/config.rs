@@ -0,0 +1,108 @@
+use crate::error::{Cc2pError, Result};
+use crate::options::ColumnOptions;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::path::Path;
+
+/// Settings read from a cc2p configuration file.
+///
+/// The file is written in TOML, with one table per column:
+///
+/// ```toml
+/// [columns.id]
+/// encoding = "DELTA_BINARY_PACKED"
+/// dictionary = false
+/// statistics = "page"
+/// bloom_filter = true
+/// bloom_filter_fpp = 0.01
+/// bloom_filter_ndv = 100000
+/// ```
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ConfigFile {
+    /// Per-column writer overrides, keyed by the column name written to the Parquet file.
+    #[serde(default)]
+    pub columns: BTreeMap<String, ColumnOptions>,
+}
+
+/// Parses a configuration file from its TOML content.
+///
+/// # Arguments
+///
+/// * `content` - The TOML content of the configuration file.
+///
+/// # Examples
+///
+/// ```rust
+/// use cc2p::config::parse_config;
+///
+/// let config = parse_config("[columns.id]\ndictionary = false\n").unwrap();
+/// assert_eq!(config.columns["id"].dictionary, Some(false));
+/// ```
+///
+/// # Returns
+///
+/// Returns the parsed configuration, or a `ConfigError` if the content is invalid.
+pub fn parse_config(content: &str) -> Result<ConfigFile> {
+    toml::from_str(content).map_err(|e| Cc2pError::ConfigError(e.to_string()))
+}
+
+/// Loads a configuration file from disk.
+///
+/// # Arguments
+///
+/// * `path` - The path of the TOML configuration file.
+///
+/// # Returns
+///
+/// Returns the parsed configuration, or an error if the file cannot be read or parsed.
+pub fn load_config(path: &Path) -> Result<ConfigFile> {
+    let content = std::fs::read_to_string(path).map_err(Cc2pError::FileError)?;
+    parse_config(&content).map_err(|e| Cc2pError::ConfigError(format!("{}: {}", path.display(), e)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::options::{ColumnEncoding, StatisticsLevel};
+
+    #[test]
+    fn test_parse_config() {
+        let config = parse_config(
+            r#"
+            [columns.id]
+            encoding = "DELTA_BINARY_PACKED"
+            dictionary = false
+
+            bloom_filter = true
+            bloom_filter_fpp = 0.01
+
+            [columns.name]
+            statistics = "chunk"
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(config.columns.len(), 2);
+        assert_eq!(config.columns["id"].encoding, Some(ColumnEncoding::DeltaBinaryPacked));
+        assert_eq!(config.columns["id"].dictionary, Some(false));
+        assert_eq!(config.columns["id"].statistics, None);
+        assert!(config.columns["id"].has_bloom_filter());
+        assert_eq!(config.columns["id"].bloom_filter_fpp, Some(0.01));
+        assert!(!config.columns["name"].has_bloom_filter());
+        assert_eq!(config.columns["name"].statistics, Some(StatisticsLevel::Chunk));
+    }
+
+    #[test]
+    fn test_parse_config_invalid() {
+        assert!(matches!(
+            parse_config("[columns.id]\nencoding = \"ZIP\"\n"),
+            Err(Cc2pError::ConfigError(_))
+        ));
+        assert!(matches!(
+            parse_config("[columns.id]\nunknown = 1\n"),
+            Err(Cc2pError::ConfigError(_))
+        ));
+        assert!(parse_config("").unwrap().columns.is_empty());
+    }
+}
@@ -1,11 +1,26 @@
+use crate::archive::{split_member, walk_tar};
+use crate::dialect::{Dialect, sniff_source};
 use crate::error::{Cc2pError, Result};
-use crate::utils::{clean_column_name, delete_if_exist};
-use arrow_schema::Schema;
-use parquet::basic::Compression;
+use crate::inference::{InferenceReport, InferenceStrategy, infer_schema_from_source, widen_type};
+use crate::options::{ConvertOptions, DEFAULT_BATCH_SIZE, OnError, OverwritePolicy, SchemaMode};
+use crate::reader::{BatchError, BatchReader, RowError, Source, open_counted_input, with_source};
+use crate::rejects::{RejectWriter, rejects_path};
+use crate::replay::Replay;
+use crate::schema::{SchemaFormat, apply_schema, schema_to_string};
+use crate::utils::{clean_column_name, is_stdio, output_path, render_template};
+use arrow_schema::{DataType, FieldRef, Schema, SchemaRef};
+use parquet::file::metadata::KeyValue;
 use parquet::file::properties::WriterProperties;
+use parquet::schema::types::ColumnPath;
+use std::cell::{Cell, RefCell};
 use std::collections::HashMap;
-use std::path::Path;
+use std::fmt;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
 use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
 
 struct Empty {}
 
@@ -51,6 +66,48 @@ pub fn remove_deduplicate_columns(sc: Schema) -> Arc<Schema> {
     Arc::new(deduplicated_schema)
 }
 
+/// Statistics of the conversion of a CSV file to Parquet.
+#[derive(Clone, Debug)]
+pub struct ConversionStats {
+    /// Number of data rows read from the CSV file, including rows that were dropped.
+    pub rows_read: u64,
+
+    /// Number of rows written to the Parquet file.
+    pub rows_written: u64,
+
+    /// Number of rows dropped because of `on_error`.
+    pub rows_rejected: u64,
+
+    /// Number of row groups in the Parquet file.
+    pub row_groups: usize,
+
+    /// Number of CSV bytes read, after decompression and before the text is decoded with `encoding`.
+    pub input_bytes: u64,
+
+    /// Size of the Parquet file in bytes.
+    pub output_bytes: u64,
+
+    /// Wall time of the conversion, including schema inference.
+    pub duration: Duration,
+
+    /// The schema of the Parquet file, after deduplication, column selection and widening.
+    pub schema: SchemaRef,
+
+    /// The dialect detected in the CSV file when `auto_dialect` is set.
+    pub dialect: Option<Dialect>,
+}
+
+impl ConversionStats {
+    /// Returns how many times smaller the Parquet file is than the CSV input, or `0.0` for an empty output.
+    pub fn compression_ratio(&self) -> f64 {
+        if self.output_bytes == 0 {
+            0.0
+        } else {
+            self.input_bytes as f64 / self.output_bytes as f64
+        }
+    }
+}
+
 /// Infers the schema of a CSV file.
 ///
 /// # Arguments
@@ -64,14 +121,87 @@ pub fn remove_deduplicate_columns(sc: Schema) -> Arc<Schema> {
 ///
 /// Returns the inferred schema if successful, otherwise returns an error.
 pub fn infer_schema(file_path: &Path, delimiter: char, has_header: bool, sampling_size: u16) -> Result<Schema> {
-    let file = std::fs::File::open(file_path).map_err(Cc2pError::FileError)?;
-    let (csv_schema, _) = arrow_csv::reader::Format::default()
-        .with_header(has_header)
-        .with_delimiter(delimiter as u8)
-        .infer_schema(file, Some(sampling_size as usize))
-        .map_err(|e| Cc2pError::SchemaError(e.to_string()))?;
-
-    Ok(csv_schema)
+    infer_schema_with_options(file_path, &ConvertOptions::new(delimiter, has_header, usize::from(sampling_size)))
+}
+
+/// Infers the schema of a CSV file using the given options.
+///
+/// If the options contain an explicit schema, it is applied on top of the inferred schema according to
+/// `schema_mode`. The result has not been deduplicated yet, see [`remove_deduplicate_columns`].
+///
+/// # Arguments
+///
+/// * `file_path` - The path of the CSV file.
+/// * `options` - The CSV reading options.
+///
+/// # Returns
+///
+/// Returns the schema if successful, otherwise returns an error.
+pub fn infer_schema_with_options(file_path: &Path, options: &ConvertOptions) -> Result<Schema> {
+    infer_schema_with_report(file_path, options).map(|(schema, _)| schema)
+}
+
+/// Infers the schema of a CSV file using the given options and reports which rows widened a column type.
+///
+/// # Arguments
+///
+/// * `file_path` - The path of the CSV file.
+/// * `options` - The CSV reading options.
+///
+/// # Returns
+///
+/// Returns the schema and the inference report if successful, otherwise returns an error.
+/// The report is empty when the schema is replaced by an explicit schema.
+pub fn infer_schema_with_report(file_path: &Path, options: &ConvertOptions) -> Result<(Schema, InferenceReport)> {
+    with_source(file_path, |source| infer_schema_from(file_path, source, options))
+}
+
+/// Infers the schema of a CSV file read from `source`, see [`infer_schema_with_report`].
+fn infer_schema_from(file_path: &Path, source: Source, options: &ConvertOptions) -> Result<(Schema, InferenceReport)> {
+    if options.auto_dialect {
+        let options = sniff_source(file_path, source, options)?.apply(options);
+        return infer_schema_from(file_path, source, &options);
+    }
+
+    match &options.schema {
+        Some(schema) if options.schema_mode == SchemaMode::Replace => Ok((schema.as_ref().clone(), InferenceReport::default())),
+        Some(schema) => {
+            let (inferred, report) = infer_schema_from_source(file_path, source, options)?;
+            Ok((apply_schema(inferred, schema, options.schema_mode)?, report))
+        }
+        None => infer_schema_from_source(file_path, source, options),
+    }
+}
+
+/// Infers the deduplicated schema of a CSV file and serializes it as a schema file.
+///
+/// The output can be edited and passed back as the `schema` option (or `--schema` on the command line) to make
+/// later conversions deterministic.
+///
+/// # Arguments
+///
+/// * `file_path` - The path of the CSV file.
+/// * `options` - The CSV reading options.
+/// * `format` - The format of the schema file.
+///
+/// # Example
+///
+/// ```
+/// use std::path::Path;
+/// use cc2p::conversion::export_schema;
+/// use cc2p::options::ConvertOptions;
+/// use cc2p::schema::SchemaFormat;
+///
+/// let content = export_schema(Path::new("testdata/sample.csv"), &ConvertOptions::default(), SchemaFormat::Json).unwrap();
+/// assert!(content.contains("\"name\": \"age\""));
+/// ```
+///
+/// # Returns
+///
+/// Returns the serialized schema if successful, otherwise returns an error.
+pub fn export_schema(file_path: &Path, options: &ConvertOptions, format: SchemaFormat) -> Result<String> {
+    let schema = remove_deduplicate_columns(infer_schema_with_options(file_path, options)?);
+    schema_to_string(&schema, format)
 }
 
 /// Converts a CSV file to Parquet format asynchronously.
@@ -85,7 +215,7 @@ pub fn infer_schema(file_path: &Path, delimiter: char, has_header: bool, samplin
 ///
 /// # Returns
 ///
-/// Returns `Ok(())` if the conversion is successful, otherwise returns an error.
+/// Returns the conversion statistics if the conversion is successful, otherwise returns an error.
 ///
 /// # Example
 ///
@@ -99,65 +229,14 @@ pub fn infer_schema(file_path: &Path, delimiter: char, has_header: bool, samplin
 ///     let delimiter = ',';
 ///     let has_header = true;
 ///
-///     convert_to_parquet(&file_path, delimiter, has_header, 10).await?;
+///     let stats = convert_to_parquet(&file_path, delimiter, has_header, 10).await?;
+///     assert_eq!(stats.rows_written, stats.rows_read);
 ///
 ///     Ok(())
 /// }
 /// ```
-pub async fn convert_to_parquet(file_path: &Path, delimiter: char, has_header: bool, sampling_size: u16) -> Result<()> {
-    // Compute the target path and delete if exists using async FS to avoid blocking
-    let target_file = file_path.with_extension("parquet");
-    let target_path = target_file
-        .to_str()
-        .ok_or_else(|| Cc2pError::Other("Failed to convert a path to string".to_string()))?;
-
-    // Delete a target file if it exists (async I/O)
-    delete_if_exist(target_path).await?;
-
-    // Offload blocking Arrow/Parquet work to a dedicated blocking thread
-    let file_path = file_path.to_path_buf();
-    let delimiter_u8 = delimiter as u8;
-    tokio::task::spawn_blocking(move || -> Result<()> {
-        let csv_schema = infer_schema(&file_path, delimiter, has_header, sampling_size)?;
-        let schema_ref = remove_deduplicate_columns(csv_schema);
-
-        // Reopen the file for reading the actual data
-        let file = std::fs::File::open(&file_path).map_err(Cc2pError::FileError)?;
-
-        let mut csv = arrow_csv::ReaderBuilder::new(schema_ref.clone())
-            .with_delimiter(delimiter_u8)
-            .with_header(has_header)
-            .build(file)
-            .map_err(|e| Cc2pError::CsvError(e.to_string()))?;
-
-        // Create the target file
-        let file = std::fs::File::create(&target_file).map_err(Cc2pError::FileError)?;
-
-        let props = WriterProperties::builder()
-            .set_compression(Compression::SNAPPY)
-            .set_created_by("cc2p".to_string())
-            .build();
-
-        let mut parquet_writer =
-            parquet::arrow::ArrowWriter::try_new(file, schema_ref, Some(props)).map_err(|e| Cc2pError::ParquetError(e.to_string()))?;
-
-        // Process batches
-        for batch in csv.by_ref() {
-            match batch {
-                Ok(batch) => parquet_writer.write(&batch).map_err(|e| Cc2pError::ParquetError(e.to_string()))?,
-                Err(e) => return Err(Cc2pError::CsvError(e.to_string())),
-            }
-        }
-
-        // Close the writer
-        parquet_writer.close().map_err(|e| Cc2pError::ParquetError(e.to_string()))?;
-
-        Ok(())
-    })
-    .await
-    .map_err(|e| Cc2pError::Other(format!("Blocking task join error: {}", e)))??;
-
-    Ok(())
+pub async fn convert_to_parquet(file_path: &Path, delimiter: char, has_header: bool, sampling_size: u16) -> Result<ConversionStats> {
+    convert_to_parquet_with_options(file_path, &ConvertOptions::new(delimiter, has_header, usize::from(sampling_size))).await
 }
 
 /// Converts a CSV file to Parquet format asynchronously with selected columns.
@@ -172,90 +251,669 @@ pub async fn convert_to_parquet(file_path: &Path, delimiter: char, has_header: b
 ///
 /// # Returns
 ///
-/// Returns `Ok(())` if the conversion is successful, otherwise returns an error.
+/// Returns the conversion statistics if the conversion is successful, otherwise returns an error.
 pub async fn convert_to_parquet_with_columns(
     file_path: &Path,
     delimiter: char,
     has_header: bool,
     sampling_size: u16,
     selected_columns: Vec<String>,
-) -> Result<()> {
-    // Compute the target path and delete if exists using async FS to avoid blocking
-    let target_file = file_path.with_extension("parquet");
-    let target_path = target_file
-        .to_str()
-        .ok_or_else(|| Cc2pError::Other("Failed to convert a path to string".to_string()))?;
+) -> Result<ConversionStats> {
+    let options = ConvertOptions {
+        selected_columns: Some(selected_columns),
+        ..ConvertOptions::new(delimiter, has_header, usize::from(sampling_size))
+    };
+    convert_to_parquet_with_options(file_path, &options).await
+}
 
-    // Delete a target file if it exists (async I/O)
-    delete_if_exist(target_path).await?;
+/// Converts a CSV file to Parquet format asynchronously using the given options.
+///
+/// When a value does not match the type of its column the conversion fails and the partial Parquet file is
+/// removed. With `widen_types` enabled, the column is widened instead and the conversion is restarted.
+/// Rows that still cannot be read or converted are handled according to `on_error`; in quarantine mode they are
+/// written to the file returned by [`rejects_path`].
+///
+/// A `file_path` of `-` reads the CSV file from stdin, and an `output` of `-` writes the Parquet file to stdout.
+/// Both can only be read or written once, so the conversion cannot be restarted to widen a column, and the schema
+/// of stdin can only be inferred from its head.
+///
+/// # Arguments
+///
+/// * `file_path` - The path of the CSV file to be converted.
+/// * `options` - The CSV reading and Parquet writing options.
+///
+/// # Returns
+///
+/// Returns the conversion statistics if the conversion is successful, otherwise returns an error.
+///
+/// # Example
+///
+/// ```
+/// use std::path::PathBuf;
+/// use cc2p::conversion::convert_to_parquet_with_options;
+/// use cc2p::options::{CompressionCodec, ConvertOptions};
+///
+/// #[tokio::main]
+/// async fn main() -> cc2p::error::Result<()> {
+///     let file_path = PathBuf::from("testdata/sample.csv");
+///     let options = ConvertOptions {
+///         compression: CompressionCodec::Zstd,
+///         compression_level: Some(9),
+///         ..ConvertOptions::default()
+///     };
+///
+///     let stats = convert_to_parquet_with_options(&file_path, &options).await?;
+///     println!("{} rows, {:.1}x smaller", stats.rows_written, stats.compression_ratio());
+///
+///     Ok(())
+/// }
+/// ```
+pub async fn convert_to_parquet_with_options(file_path: &Path, options: &ConvertOptions) -> Result<ConversionStats> {
+    let start = Instant::now();
+    let target_file = prepare_target(file_path, options).await?;
 
     // Offload blocking Arrow/Parquet work to a dedicated blocking thread
     let file_path = file_path.to_path_buf();
-    let delimiter_u8 = delimiter as u8;
-    tokio::task::spawn_blocking(move || -> Result<()> {
-        let csv_schema = infer_schema(&file_path, delimiter, has_header, sampling_size)?;
-        let full_schema = remove_deduplicate_columns(csv_schema);
-
-        let mut projection_indices = Vec::new();
-        let mut projected_fields = Vec::new();
-
-        for (i, field) in full_schema.fields().iter().enumerate() {
-            if selected_columns.contains(field.name()) {
-                projection_indices.push(i);
-                projected_fields.push(field.clone());
-            }
+    let options = options.clone();
+    let mut stats =
+        tokio::task::spawn_blocking(move || with_source(&file_path, |source| convert_source(&file_path, source, &target_file, options)))
+            .await
+            .map_err(|e| Cc2pError::Other(format!("Blocking task join error: {}", e)))??;
+
+    stats.duration = start.elapsed();
+    Ok(stats)
+}
+
+/// Converts the CSV members of a tar archive to Parquet in a single pass over the archive.
+///
+/// Each member is converted while the archive streams past it. Like stdin, only the head that is sampled for the
+/// schema is kept in memory, see [`convert_to_parquet_with_options`]. Options that read a member more than once,
+/// i.e. `widen_types` and any inference strategy but `Head`, open every member on its own instead, which reads
+/// the archive up to that member each time.
+///
+/// # Arguments
+///
+/// * `archive` - The path of the tar archive.
+/// * `members` - The paths of the members to convert, e.g. `drop.tar.gz/sales/jan.csv`, see [`find_files`](crate::utils::find_files).
+/// * `options` - The CSV reading and Parquet writing options.
+///
+/// # Returns
+///
+/// Returns the result of every member and the time spent on it, in the order of `members`.
+pub async fn convert_tar_members(archive: &Path, members: &[PathBuf], options: &ConvertOptions) -> Vec<(Result<ConversionStats>, Duration)> {
+    let mut results = Vec::with_capacity(members.len());
+    if options.widen_types || options.inference != InferenceStrategy::Head {
+        for member in members {
+            let start = Instant::now();
+            results.push((convert_to_parquet_with_options(member, options).await, start.elapsed()));
         }
+        return results;
+    }
 
-        if projection_indices.is_empty() {
-            return Err(Cc2pError::Other("No columns selected for export".to_string()));
+    // Members that are not found in the archive keep their error
+    let mut targets = HashMap::new();
+    for (index, member) in members.iter().enumerate() {
+        let start = Instant::now();
+        match (prepare_target(member, options).await, split_member(member)) {
+            (Ok(target_file), Some((_, name))) => {
+                targets.insert(name, (index, target_file));
+                results.push((Err(member_not_found(member)), Duration::ZERO));
+            }
+            (Ok(_), None) => results.push((Err(member_not_found(member)), start.elapsed())),
+            (Err(e), _) => results.push((Err(e), start.elapsed())),
         }
+    }
 
-        let projected_schema = Arc::new(Schema::new_with_metadata(projected_fields, full_schema.metadata().clone()));
+    let archive = archive.to_path_buf();
+    let paths = members.to_vec();
+    let options = options.clone();
+    tokio::task::spawn_blocking(move || {
+        let walked = walk_tar(&archive, |name, data| {
+            if let Some((index, target_file)) = targets.remove(name) {
+                let start = Instant::now();
+                let replay = RefCell::new(Replay::new(data));
+                let result = convert_source(&paths[index], Source::Stream(&replay), &target_file, options.clone());
+                let duration = start.elapsed();
+                results[index] = (result.map(|stats| ConversionStats { duration, ..stats }), duration);
+            }
+        });
+        if let Err(e) = walked {
+            for (index, _) in targets.into_values() {
+                results[index].0 = Err(Cc2pError::FileError(io::Error::new(e.kind(), e.to_string())));
+            }
+        }
+        results
+    })
+    .await
+    .unwrap_or_else(|e| {
+        let error = || Err(Cc2pError::Other(format!("Blocking task join error: {}", e)));
+        (0..members.len()).map(|_| (error(), Duration::ZERO)).collect()
+    })
+}
 
-        // Reopen the file for reading the actual data
-        let file = std::fs::File::open(&file_path).map_err(Cc2pError::FileError)?;
+/// Returns the error of a member that is not in its archive.
+fn member_not_found(member: &Path) -> Cc2pError {
+    Cc2pError::FileError(io::Error::new(
+        io::ErrorKind::NotFound,
+        format!("{} is not in the archive", member.display()),
+    ))
+}
 
-        let mut csv = arrow_csv::ReaderBuilder::new(full_schema)
-            .with_delimiter(delimiter_u8)
-            .with_header(has_header)
-            .with_projection(projection_indices)
-            .build(file)
-            .map_err(|e| Cc2pError::CsvError(e.to_string()))?;
+/// Validates the options and returns the path of the Parquet file for a CSV file, after checking whether an
+/// existing file may be replaced and creating its directory.
+async fn prepare_target(file_path: &Path, options: &ConvertOptions) -> Result<PathBuf> {
+    // Validate the settings before touching any file
+    options.validate()?;
 
-        // Create the target file
-        let file = std::fs::File::create(&target_file).map_err(Cc2pError::FileError)?;
+    // Compute the target path and check whether an existing file may be replaced
+    let target_file = target_path(file_path, options)?;
 
-        let props = WriterProperties::builder()
-            .set_compression(Compression::SNAPPY)
-            .set_created_by("cc2p".to_string())
-            .build();
+    if !is_stdio(&target_file) {
+        check_overwrite(file_path, &target_file, options.overwrite).await?;
 
-        let mut parquet_writer =
-            parquet::arrow::ArrowWriter::try_new(file, projected_schema, Some(props)).map_err(|e| Cc2pError::ParquetError(e.to_string()))?;
+        // Members of an archive, --output-dir and the output template may need new directories
+        if let Some(parent) = target_file.parent()
+            && !parent.as_os_str().is_empty()
+        {
+            tokio::fs::create_dir_all(parent).await.map_err(Cc2pError::FileError)?;
+        }
+    }
+    Ok(target_file)
+}
 
-        // Process batches
-        for batch in csv.by_ref() {
-            match batch {
-                Ok(batch) => parquet_writer.write(&batch).map_err(|e| Cc2pError::ParquetError(e.to_string()))?,
-                Err(e) => return Err(Cc2pError::CsvError(e.to_string())),
+/// Converts a CSV file read from `source` to `target_file`, see [`convert_to_parquet_with_options`].
+///
+/// Returns the conversion statistics without the duration.
+fn convert_source(file_path: &Path, source: Source, target_file: &Path, options: ConvertOptions) -> Result<ConversionStats> {
+    // Stdin and stdout can only be read and written once, so a column cannot be widened by restarting
+    let restartable = matches!(source, Source::Path) && !is_stdio(target_file);
+
+    let dialect = if options.auto_dialect {
+        Some(sniff_source(file_path, source, &options)?)
+    } else {
+        None
+    };
+    let options = match dialect {
+        Some(dialect) => dialect.apply(&options),
+        None => options,
+    };
+
+    let (csv_schema, _) = infer_schema_from(file_path, source, &options)?;
+    let mut full_schema = remove_deduplicate_columns(csv_schema);
+    let full_schema_names: Vec<String> = full_schema.fields().iter().map(|f| f.name().clone()).collect();
+
+    let projection: Vec<usize> = match &options.selected_columns {
+        Some(selected_columns) => {
+            let projection: Vec<usize> = full_schema
+                .fields()
+                .iter()
+                .enumerate()
+                .filter(|(_, field)| selected_columns.contains(field.name()))
+                .map(|(i, _)| i)
+                .collect();
+
+            if projection.is_empty() {
+                return Err(Cc2pError::Other("No columns selected for export".to_string()));
             }
+            projection
         }
+        None => (0..full_schema.fields().len()).collect(),
+    };
+
+    let props = writer_properties(&options, &full_schema_names)?;
+
+    loop {
+        let result = write_parquet(file_path, source, target_file, &full_schema, &projection, &options, props.clone());
+
+        match result {
+            Ok(stats) => return Ok(ConversionStats { dialect, ..stats }),
+            Err(WriteError::InvalidValue(invalid)) => {
+                let column = projection[invalid.field];
+                let widened = match widen_type(&invalid.data_type, &invalid.normalized) {
+                    Some(_) if options.widen_types && !restartable => {
+                        return Err(Cc2pError::Other(format!(
+                            "{}; the column cannot be widened when reading from stdin or writing to stdout",
+                            invalid.into_error(file_path)
+                        )));
+                    }
+                    Some(data_type) if options.widen_types => data_type,
+                    _ => return Err(invalid.into_error(file_path)),
+                };
+
+                // Restart the conversion with the wider column type
+                let mut fields: Vec<FieldRef> = full_schema.fields().iter().cloned().collect();
+                fields[column] = Arc::new(fields[column].as_ref().clone().with_data_type(widened));
+                full_schema = Arc::new(Schema::new_with_metadata(fields, full_schema.metadata().clone()));
+            }
+            Err(WriteError::Failed(e)) => return Err(e),
+        }
+    }
+}
 
-        // Close the writer
-        parquet_writer.close().map_err(|e| Cc2pError::ParquetError(e.to_string()))?;
+/// Checks whether the existing Parquet file of a CSV file may be replaced.
+///
+/// # Arguments
+///
+/// * `file_path` - The path of the CSV file.
+/// * `target_file` - The path of the Parquet file.
+/// * `policy` - What happens when the Parquet file already exists.
+///
+/// # Returns
+///
+/// Returns `Ok(())` if the Parquet file is missing or may be replaced, otherwise returns an `OutputExists` error.
+async fn check_overwrite(file_path: &Path, target_file: &Path, policy: OverwritePolicy) -> Result<()> {
+    if policy == OverwritePolicy::Always {
+        return Ok(());
+    }
+    let target_modified = match tokio::fs::metadata(target_file).await {
+        Ok(metadata) => metadata.modified().map_err(Cc2pError::FileError)?,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(()),
+        Err(e) => return Err(Cc2pError::FileError(e)),
+    };
+
+    // Members of an archive are as new as the archive, and stdin is always newer
+    let input = split_member(file_path).map_or(file_path, |(archive, _)| archive);
+    let keep = match policy {
+        OverwritePolicy::Always => false,
+        OverwritePolicy::Never => true,
+        OverwritePolicy::IfNewer if is_stdio(input) => false,
+        OverwritePolicy::IfNewer => {
+            let metadata = tokio::fs::metadata(input).await.map_err(Cc2pError::FileError)?;
+            metadata.modified().map_err(Cc2pError::FileError)? <= target_modified
+        }
+    };
 
+    if keep {
+        Err(Cc2pError::OutputExists(target_file.to_path_buf()))
+    } else {
         Ok(())
+    }
+}
+
+/// Number of partial Parquet files created by this process, which keeps their names unique.
+static PARTIAL_FILES: AtomicU64 = AtomicU64::new(0);
+
+/// Returns a new path a Parquet file is written to before it is moved to `target_file`, see [`persist`].
+///
+/// The file is hidden and in the same directory as the target, so the rename replaces the target atomically.
+/// Every call returns another path, so conversions with the same target never write to the same file.
+fn partial_path(target_file: &Path) -> PathBuf {
+    let name = target_file.file_name().unwrap_or_default().to_string_lossy();
+    let count = PARTIAL_FILES.fetch_add(1, Ordering::Relaxed);
+    target_file.with_file_name(format!(".{}.{}.{}.partial", name, std::process::id(), count))
+}
+
+/// A Parquet file that is written at a [`partial_path`] and removed when it is dropped, so a failed conversion
+/// never leaves a half-written file behind and the previous file stays in place.
+struct PartialFile(PathBuf);
+
+impl Drop for PartialFile {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.0);
+    }
+}
+
+/// Moves a complete Parquet file from its partial path to `target_file`.
+///
+/// With [`OverwritePolicy::Never`] the file is hard-linked to the target instead of renamed over it, which fails
+/// if the target was created since it was checked. The partial file is then removed by its [`PartialFile`].
+///
+/// # Returns
+///
+/// Returns `Ok(())` if the file is in place, an `OutputExists` error if the policy keeps a target that exists by
+/// now, otherwise a `FileError`.
+fn persist(partial_file: &Path, target_file: &Path, policy: OverwritePolicy) -> Result<()> {
+    if policy != OverwritePolicy::Never {
+        return std::fs::rename(partial_file, target_file).map_err(Cc2pError::FileError);
+    }
+
+    match std::fs::hard_link(partial_file, target_file) {
+        Err(e) if e.kind() == io::ErrorKind::AlreadyExists => Err(Cc2pError::OutputExists(target_file.to_path_buf())),
+        linked => linked.map_err(Cc2pError::FileError),
+    }
+}
+
+/// Returns the path of the Parquet file written for a CSV file.
+///
+/// This is `output` if it is set. Otherwise the file is named after `output_template` and written next to the
+/// CSV file, or below `output_dir` in the same directory relative to `input_root`. A template that starts with
+/// `{parent}/` replaces the directory of the CSV file rather than adding to it.
+///
+/// # Arguments
+///
+/// * `file_path` - The path of the CSV file.
+/// * `options` - The conversion options.
+///
+/// # Returns
+///
+/// Returns the path of the Parquet file, or a `ConfigError` if the template is invalid or no output path is set
+/// for stdin.
+///
+/// # Examples
+///
+/// ```rust
+/// use std::path::{Path, PathBuf};
+/// use cc2p::conversion::target_path;
+/// use cc2p::options::ConvertOptions;
+///
+/// let options = ConvertOptions {
+///     output_dir: Some(PathBuf::from("out")),
+///     input_root: Some(PathBuf::from("data")),
+///     output_template: Some("{stem}.{compression}.parquet".to_string()),
+///     ..ConvertOptions::default()
+/// };
+/// let target = target_path(Path::new("data/2026/jan.csv"), &options).unwrap();
+/// assert_eq!(target, PathBuf::from("out/2026/jan.snappy.parquet"));
+/// ```
+pub fn target_path(file_path: &Path, options: &ConvertOptions) -> Result<PathBuf> {
+    if let Some(output) = &options.output {
+        return Ok(output.clone());
+    }
+    if is_stdio(file_path) {
+        return Err(Cc2pError::ConfigError(
+            "an output path is required when reading from stdin".to_string(),
+        ));
+    }
+
+    let default = output_path(file_path, "parquet");
+    let mut directory = default.parent().unwrap_or(Path::new("")).to_path_buf();
+    let name = match &options.output_template {
+        Some(template) => {
+            let stem = default.file_stem().unwrap_or_default().to_string_lossy().into_owned();
+            let parent = directory.file_name().unwrap_or_default().to_string_lossy().into_owned();
+            let date = chrono::Local::now().format("%Y-%m-%d").to_string();
+            let compression = options.compression.to_string();
+            let values = [
+                ("stem", &*stem),
+                ("parent", &*parent),
+                ("date", &*date),
+                ("compression", &*compression),
+            ];
+
+            // `{parent}/` recreates the directory of the CSV file, so it takes the place of that directory
+            let template = match template.strip_prefix("{parent}/") {
+                Some(rest) => {
+                    directory.pop();
+                    // A file in the current directory has no parent name, and so no directory to recreate
+                    if parent.is_empty() { rest } else { template.as_str() }
+                }
+                None => template,
+            };
+            PathBuf::from(render_template(template, &values)?)
+        }
+        None => PathBuf::from(default.file_name().unwrap_or_default()),
+    };
+
+    let directory = match &options.output_dir {
+        Some(output_dir) => {
+            let relative = options.input_root.as_deref().and_then(|root| directory.strip_prefix(root).ok());
+            output_dir.join(relative.unwrap_or(Path::new("")))
+        }
+        None => directory,
+    };
+    Ok(directory.join(name))
+}
+
+/// A value that cannot be parsed with the type of its column.
+struct InvalidValue {
+    /// The 1-based line of the value in the CSV file.
+    line: u64,
+
+    /// The index of the column in the written schema.
+    field: usize,
+
+    /// The name of the column.
+    column: String,
+
+    /// The type of the column.
+    data_type: DataType,
+
+    /// The raw value, as it is in the CSV file.
+    value: String,
+
+    /// The value after applying the null, boolean, date and number settings, which decides how the column is widened.
+    normalized: String,
+}
+
+impl InvalidValue {
+    /// Converts the invalid value into a [`Cc2pError::ValueError`] for the given CSV file.
+    fn into_error(self, path: &Path) -> Cc2pError {
+        Cc2pError::ValueError {
+            path: path.to_path_buf(),
+            line: self.line,
+            column: self.column,
+            value: self.value,
+            expected: self.data_type,
+        }
+    }
+}
+
+impl fmt::Display for InvalidValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "cannot parse value '{}' of column '{}' as {}",
+            self.value, self.column, self.data_type
+        )
+    }
+}
+
+/// Errors of a single attempt to write the Parquet file.
+enum WriteError {
+    /// A value does not match the type of its column, the column may be widened and the attempt retried.
+    InvalidValue(InvalidValue),
+
+    /// Any other error.
+    Failed(Cc2pError),
+}
+
+impl From<Cc2pError> for WriteError {
+    fn from(e: Cc2pError) -> Self {
+        WriteError::Failed(e)
+    }
+}
+
+/// Reads the projected columns of `file_path` and writes them as Parquet to `target_file`.
+///
+/// Rows that cannot be read or converted fail the attempt, unless `options.on_error` drops them.
+///
+/// # Arguments
+///
+/// * `file_path` - The path of the CSV file.
+/// * `source` - Where the CSV file is read from.
+/// * `target_file` - The path of the Parquet file.
+/// * `file_schema` - The schema of all columns of the CSV file.
+/// * `projection` - The indices of the columns written to the Parquet file.
+/// * `options` - The CSV reading options.
+/// * `props` - The Parquet writer properties.
+///
+/// # Returns
+///
+/// Returns the conversion statistics without the duration and dialect.
+fn write_parquet(
+    file_path: &Path,
+    source: Source,
+    target_file: &Path,
+    file_schema: &Schema,
+    projection: &[usize],
+    options: &ConvertOptions,
+    props: WriterProperties,
+) -> std::result::Result<ConversionStats, WriteError> {
+    // The rejected rows go next to the Parquet file once it is complete, like the Parquet file itself
+    let rejects_file = PartialFile(partial_path(&rejects_path(target_file)));
+    let mut rejects = RejectWriter::new(rejects_file.0.clone(), options)?;
+    let input_bytes = Rc::new(Cell::new(0));
+    let mut reader = BatchReader::new(
+        open_counted_input(file_path, source, options, input_bytes.clone())?,
+        file_schema,
+        projection,
+        options,
+    )?;
+    let schema = reader.schema();
+
+    // Create the file, which replaces the target once it is complete
+    let partial_file = (!is_stdio(target_file)).then(|| PartialFile(partial_path(target_file)));
+    let file: Box<dyn Write + Send> = match &partial_file {
+        Some(PartialFile(path)) => Box::new(std::fs::File::create(path).map_err(Cc2pError::FileError)?),
+        None => Box::new(io::stdout()),
+    };
+    let mut parquet_writer =
+        parquet::arrow::ArrowWriter::try_new(file, schema.clone(), Some(props)).map_err(|e| Cc2pError::ParquetError(e.to_string()))?;
+
+    // Process batches, dropping the rows that cannot be converted if options.on_error allows it
+    loop {
+        let row = match reader.next_batch() {
+            Ok(Some(batch)) => {
+                parquet_writer.write(&batch).map_err(|e| Cc2pError::ParquetError(e.to_string()))?;
+                continue;
+            }
+            Ok(None) => break,
+            Err(BatchError::BadRow(row)) => row,
+            Err(BatchError::Failed(e)) => return Err(e.into()),
+        };
+
+        let error = match row.error {
+            RowError::InvalidValue { field, value, normalized } => {
+                let invalid = InvalidValue {
+                    line: row.line,
+                    field,
+                    column: schema.field(field).name().clone(),
+                    data_type: schema.field(field).data_type().clone(),
+                    value,
+                    normalized,
+                };
+                // Widening takes precedence over dropping the row
+                if options.on_error == OnError::Fail || (options.widen_types && widen_type(&invalid.data_type, &invalid.normalized).is_some()) {
+                    return Err(WriteError::InvalidValue(invalid));
+                }
+                invalid.to_string()
+            }
+            RowError::Malformed(message) if options.on_error == OnError::Fail => {
+                return Err(Cc2pError::RecordError {
+                    path: file_path.to_path_buf(),
+                    line: row.line,
+                    message,
+                }
+                .into());
+            }
+            RowError::Malformed(message) => message,
+        };
+        rejects.reject(row.line, &row.raw, &error)?;
+    }
+
+    // Close the writers
+    let metadata = parquet_writer.finish().map_err(|e| Cc2pError::ParquetError(e.to_string()))?;
+    let rows_rejected = rejects.finish()? as u64;
+    let rows_written = u64::try_from(metadata.file_metadata().num_rows()).unwrap_or_default();
+    let output_bytes = parquet_writer.bytes_written() as u64;
+
+    // Move the complete files into place, the rejects of a previous conversion belong to the previous file
+    drop(parquet_writer);
+    if let Some(PartialFile(path)) = &partial_file {
+        persist(path, target_file, options.overwrite)?;
+        if options.on_error == OnError::Quarantine && rows_rejected > 0 {
+            std::fs::rename(&rejects_file.0, rejects_path(target_file)).map_err(Cc2pError::FileError)?;
+        } else {
+            let _ = std::fs::remove_file(rejects_path(target_file));
+        }
+    }
+
+    Ok(ConversionStats {
+        rows_read: rows_written + rows_rejected,
+        rows_written,
+        rows_rejected,
+        row_groups: metadata.num_row_groups(),
+        input_bytes: input_bytes.get(),
+        output_bytes,
+        duration: Duration::ZERO,
+        schema,
+        dialect: None,
     })
-    .await
-    .map_err(|e| Cc2pError::Other(format!("Blocking task join error: {}", e)))??;
+}
+
+/// Builds the Parquet writer properties for the given options.
+///
+/// The effective sizing values are recorded in the file's key-value metadata under the `cc2p.` prefix.
+/// Column overrides are matched against `column_names`, the deduplicated names of the file's columns.
+fn writer_properties(options: &ConvertOptions, column_names: &[String]) -> Result<WriterProperties> {
+    let mut builder = WriterProperties::builder()
+        .set_compression(options.compression()?)
+        .set_created_by("cc2p".to_string());
+
+    if let Some(rows) = options.max_row_group_rows {
+        builder = builder.set_max_row_group_row_count(Some(rows));
+    }
+    if let Some(bytes) = options.max_row_group_bytes {
+        builder = builder.set_max_row_group_bytes(Some(bytes));
+    }
+    if let Some(size) = options.data_page_size {
+        builder = builder.set_data_page_size_limit(size);
+    }
+    if let Some(size) = options.dictionary_page_size {
+        builder = builder.set_dictionary_page_size_limit(size);
+    }
+    if let Some(size) = options.write_batch_size {
+        builder = builder.set_write_batch_size(size);
+    }
+
+    for (name, column) in &options.column_options {
+        if !column_names.contains(name) {
+            return Err(Cc2pError::ConfigError(format!("column options refer to unknown column '{}'", name)));
+        }
+
+        let path = ColumnPath::from(name.as_str());
+        if let Some(encoding) = column.encoding {
+            builder = builder.set_column_encoding(path.clone(), encoding.to_encoding());
+        }
+        if let Some(dictionary) = column.dictionary {
+            builder = builder.set_column_dictionary_enabled(path.clone(), dictionary);
+        }
+        if let Some(statistics) = column.statistics {
+            builder = builder.set_column_statistics_enabled(path.clone(), statistics.to_enabled_statistics());
+        }
+        if let Some(bloom_filter) = column.bloom_filter {
+            builder = builder.set_column_bloom_filter_enabled(path.clone(), bloom_filter);
+        }
+        // Setting the fpp or ndv implicitly enables the bloom filter, so only apply them when it is enabled
+        if column.has_bloom_filter() {
+            if let Some(fpp) = column.bloom_filter_fpp {
+                builder = builder.set_column_bloom_filter_fpp(path.clone(), fpp);
+            }
+            if let Some(ndv) = column.bloom_filter_ndv {
+                builder = builder.set_column_bloom_filter_max_ndv(path, ndv);
+            }
+        }
+    }
 
-    Ok(())
+    let props = builder.build();
+    let compression = match options.compression_level {
+        Some(level) => format!("{}({})", options.compression, level),
+        None => options.compression.to_string(),
+    };
+    let optional = |value: Option<usize>| value.map_or_else(|| "unlimited".to_string(), |v| v.to_string());
+    let metadata = vec![
+        KeyValue::new("cc2p.compression".to_string(), compression),
+        KeyValue::new(
+            "cc2p.batch_size".to_string(),
+            options.batch_size.unwrap_or(DEFAULT_BATCH_SIZE).to_string(),
+        ),
+        KeyValue::new("cc2p.max_row_group_rows".to_string(), optional(props.max_row_group_row_count())),
+        KeyValue::new("cc2p.max_row_group_bytes".to_string(), optional(props.max_row_group_bytes())),
+        KeyValue::new("cc2p.data_page_size".to_string(), props.data_page_size_limit().to_string()),
+        KeyValue::new(
+            "cc2p.dictionary_page_size".to_string(),
+            props.dictionary_page_size_limit().to_string(),
+        ),
+        KeyValue::new("cc2p.write_batch_size".to_string(), props.write_batch_size().to_string()),
+    ];
+
+    Ok(props.into_builder().set_key_value_metadata(Some(metadata)).build())
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use arrow_schema::DataType;
+    use crate::options::{ColumnEncoding, ColumnOptions, CompressionCodec, StatisticsLevel};
     use arrow_schema::Field;
     use parquet::file::reader::FileReader;
     use std::fs;
@@ -435,4 +1093,899 @@ mod tests {
         let _ = fs::remove_file(temp_csv);
         let _ = fs::remove_file(parquet_file);
     }
+
+    #[tokio::test]
+    async fn test_convert_to_parquet_with_compression() {
+        let mut source_file = std::env::current_dir().unwrap();
+        source_file.push("testdata");
+        source_file.push("sample.csv");
+
+        let mut temp_csv = std::env::temp_dir();
+        temp_csv.push("temp_sample_for_compression_test.csv");
+        fs::copy(&source_file, &temp_csv).unwrap();
+
+        let options = ConvertOptions {
+            compression: CompressionCodec::Zstd,
+            compression_level: Some(9),
+            ..ConvertOptions::default()
+        };
+        let result = convert_to_parquet_with_options(&temp_csv, &options).await;
+        assert!(result.is_ok());
+
+        let parquet_file = temp_csv.with_extension("parquet");
+        let file = std::fs::File::open(&parquet_file).unwrap();
+        let reader = parquet::file::reader::SerializedFileReader::new(file).unwrap();
+        let column = reader.metadata().row_group(0).column(0);
+        assert!(matches!(column.compression(), parquet::basic::Compression::ZSTD(_)));
+
+        // Clean up
+        let _ = fs::remove_file(temp_csv);
+        let _ = fs::remove_file(parquet_file);
+    }
+
+    #[tokio::test]
+    async fn test_convert_to_parquet_with_invalid_compression_level() {
+        let options = ConvertOptions {
+            compression: CompressionCodec::Snappy,
+            compression_level: Some(3),
+            ..ConvertOptions::default()
+        };
+        let result = convert_to_parquet_with_options(&PathBuf::from("testdata/sample.csv"), &options).await;
+        assert!(matches!(result, Err(Cc2pError::ConfigError(_))));
+    }
+
+    #[tokio::test]
+    async fn test_convert_to_parquet_with_sizing() {
+        let mut source_file = std::env::current_dir().unwrap();
+        source_file.push("testdata");
+        source_file.push("sample.csv");
+
+        let mut temp_csv = std::env::temp_dir();
+        temp_csv.push("temp_sample_for_sizing_test.csv");
+        fs::copy(&source_file, &temp_csv).unwrap();
+
+        let options = ConvertOptions {
+            batch_size: Some(1),
+            max_row_group_rows: Some(2),
+            data_page_size: Some(4096),
+            ..ConvertOptions::default()
+        };
+        let stats = convert_to_parquet_with_options(&temp_csv, &options).await.unwrap();
+        assert_eq!(stats.rows_read, 5);
+        assert_eq!(stats.rows_written, 5);
+        assert_eq!(stats.rows_rejected, 0);
+        assert_eq!(stats.row_groups, 3);
+        assert_eq!(stats.input_bytes, fs::metadata(&temp_csv).unwrap().len());
+        assert!(stats.compression_ratio() > 0.0);
+
+        let parquet_file = temp_csv.with_extension("parquet");
+        assert_eq!(stats.output_bytes, fs::metadata(&parquet_file).unwrap().len());
+        let file = std::fs::File::open(&parquet_file).unwrap();
+        let reader = parquet::file::reader::SerializedFileReader::new(file).unwrap();
+        let metadata = reader.metadata();
+        assert_eq!(metadata.num_row_groups(), 3);
+
+        let key_value_metadata = metadata.file_metadata().key_value_metadata().unwrap();
+        let value_of = |key: &str| key_value_metadata.iter().find(|kv| kv.key == key).and_then(|kv| kv.value.clone());
+        assert_eq!(value_of("cc2p.max_row_group_rows").as_deref(), Some("2"));
+        assert_eq!(value_of("cc2p.data_page_size").as_deref(), Some("4096"));
+        assert_eq!(value_of("cc2p.batch_size").as_deref(), Some("1"));
+
+        // Clean up
+        let _ = fs::remove_file(temp_csv);
+        let _ = fs::remove_file(parquet_file);
+    }
+
+    #[tokio::test]
+    async fn test_convert_to_parquet_with_column_options() {
+        let mut source_file = std::env::current_dir().unwrap();
+        source_file.push("testdata");
+        source_file.push("sample_empty_header.csv");
+
+        let mut temp_csv = std::env::temp_dir();
+        temp_csv.push("temp_sample_for_column_options_test.csv");
+        fs::copy(&source_file, &temp_csv).unwrap();
+
+        // The empty header is renamed to column_1 before the overrides are applied
+        let mut options = ConvertOptions::default();
+        options.column_options.insert(
+            "column_1".to_string(),
+            ColumnOptions {
+                encoding: Some(ColumnEncoding::DeltaBinaryPacked),
+                dictionary: Some(false),
+                statistics: Some(StatisticsLevel::None),
+                ..ColumnOptions::default()
+            },
+        );
+        let result = convert_to_parquet_with_options(&temp_csv, &options).await;
+        assert!(result.is_ok(), "{:?}", result);
+
+        let parquet_file = temp_csv.with_extension("parquet");
+        let file = std::fs::File::open(&parquet_file).unwrap();
+        let reader = parquet::file::reader::SerializedFileReader::new(file).unwrap();
+        let row_group = reader.metadata().row_group(0);
+
+        let column = row_group.column(1);
+        assert_eq!(column.column_path().string(), "column_1");
+        assert!(column.encodings().any(|e| e == parquet::basic::Encoding::DELTA_BINARY_PACKED));
+        assert!(column.dictionary_page_offset().is_none());
+        assert!(column.statistics().is_none());
+
+        // Other columns keep the defaults
+        assert!(row_group.column(0).dictionary_page_offset().is_some());
+        assert!(row_group.column(0).statistics().is_some());
+
+        // Clean up
+        let _ = fs::remove_file(temp_csv);
+        let _ = fs::remove_file(parquet_file);
+    }
+
+    #[tokio::test]
+    async fn test_convert_to_parquet_with_unknown_column_options() {
+        let mut temp_csv = std::env::temp_dir();
+        temp_csv.push("temp_sample_for_unknown_column_options_test.csv");
+        fs::copy("testdata/sample.csv", &temp_csv).unwrap();
+
+        let mut options = ConvertOptions::default();
+        options.column_options.insert("missing".to_string(), ColumnOptions::default());
+        let result = convert_to_parquet_with_options(&temp_csv, &options).await;
+        assert!(matches!(result, Err(Cc2pError::ConfigError(_))));
+
+        // Clean up
+        let _ = fs::remove_file(temp_csv.with_extension("parquet"));
+        let _ = fs::remove_file(temp_csv);
+    }
+
+    #[tokio::test]
+    async fn test_convert_to_parquet_with_bloom_filter() {
+        let mut temp_csv = std::env::temp_dir();
+        temp_csv.push("temp_sample_for_bloom_filter_test.csv");
+        fs::copy("testdata/sample.csv", &temp_csv).unwrap();
+
+        let mut options = ConvertOptions::default();
+        options.column_options.insert(
+            "name".to_string(),
+            ColumnOptions {
+                bloom_filter: Some(true),
+                bloom_filter_fpp: Some(0.01),
+                bloom_filter_ndv: Some(1000),
+                ..ColumnOptions::default()
+            },
+        );
+        let result = convert_to_parquet_with_options(&temp_csv, &options).await;
+        assert!(result.is_ok(), "{:?}", result);
+
+        let parquet_file = temp_csv.with_extension("parquet");
+        let file = std::fs::File::open(&parquet_file).unwrap();
+        let reader = parquet::file::reader::SerializedFileReader::new(file).unwrap();
+        let row_group = reader.metadata().row_group(0);
+        assert!(row_group.column(0).bloom_filter_offset().is_some());
+        assert!(row_group.column(1).bloom_filter_offset().is_none());
+
+        // Clean up
+        let _ = fs::remove_file(temp_csv);
+        let _ = fs::remove_file(parquet_file);
+    }
+
+    #[tokio::test]
+    async fn test_convert_to_parquet_with_schema_override() {
+        let mut temp_csv = std::env::temp_dir();
+        temp_csv.push("temp_sample_for_schema_override_test.csv");
+        fs::write(&temp_csv, "id,amount\n1,10\n2,abc\n").unwrap();
+
+        // Inference on the first row alone would type "amount" as Int64 and fail on "abc"
+        let options = ConvertOptions {
+            sampling_size: 1,
+            schema: Some(Arc::new(Schema::new(vec![Field::new("amount", DataType::Utf8, true)]))),
+            ..ConvertOptions::default()
+        };
+        let schema = infer_schema_with_options(&temp_csv, &options).unwrap();
+        assert_eq!(schema.field(0).data_type(), &DataType::Int64);
+        assert_eq!(schema.field(1).data_type(), &DataType::Utf8);
+
+        let result = convert_to_parquet_with_options(&temp_csv, &options).await;
+        assert!(result.is_ok(), "{:?}", result);
+
+        // Clean up
+        let _ = fs::remove_file(temp_csv.with_extension("parquet"));
+        let _ = fs::remove_file(temp_csv);
+    }
+
+    #[tokio::test]
+    async fn test_convert_to_parquet_with_widen_types() {
+        let mut temp_csv = std::env::temp_dir();
+        temp_csv.push("temp_sample_for_widen_types_test.csv");
+        fs::write(&temp_csv, "id,amount,price\n1,10,10\n2,2.5,2.5\n3,abc,3\n").unwrap();
+
+        // Without widening the mismatch fails and no Parquet file is left behind
+        let options = ConvertOptions {
+            sampling_size: 1,
+            batch_size: Some(1),
+            ..ConvertOptions::default()
+        };
+        let result = convert_to_parquet_with_options(&temp_csv, &options).await;
+        match result {
+            Err(Cc2pError::ValueError {
+                path,
+                line,
+                column,
+                value,
+                expected,
+            }) => {
+                assert_eq!(path, temp_csv);
+                assert_eq!(line, 3);
+                assert_eq!(column, "amount");
+                assert_eq!(value, "2.5");
+                assert_eq!(expected, DataType::Int64);
+            }
+            _ => panic!("Unexpected result: {:?}", result),
+        }
+        let parquet_file = temp_csv.with_extension("parquet");
+        assert!(!parquet_file.exists());
+
+        let options = ConvertOptions {
+            widen_types: true,
+            ..options
+        };
+        let result = convert_to_parquet_with_options(&temp_csv, &options).await;
+        assert!(result.is_ok(), "{:?}", result);
+
+        let file = std::fs::File::open(&parquet_file).unwrap();
+        let builder = parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder::try_new(file).unwrap();
+        let schema = builder.schema();
+        assert_eq!(schema.field(0).data_type(), &DataType::Int64);
+        assert_eq!(schema.field(1).data_type(), &DataType::Utf8);
+        assert_eq!(schema.field(2).data_type(), &DataType::Float64);
+        assert_eq!(builder.metadata().file_metadata().num_rows(), 3);
+
+        // Clean up
+        let _ = fs::remove_file(temp_csv);
+        let _ = fs::remove_file(parquet_file);
+    }
+
+    #[tokio::test]
+    async fn test_convert_to_parquet_with_value_tokens() {
+        let mut temp_csv = std::env::temp_dir();
+        temp_csv.push("temp_sample_for_value_tokens_test.csv");
+        fs::write(&temp_csv, "id,active,note\n1, yes ,NULL\n\\N,no, kept \n").unwrap();
+
+        let options = ConvertOptions {
+            null_values: vec!["NULL".to_string(), "\\N".to_string()],
+            true_values: vec!["yes".to_string()],
+            false_values: vec!["no".to_string()],
+            trim: true,
+            ..ConvertOptions::default()
+        };
+        let result = convert_to_parquet_with_options(&temp_csv, &options).await;
+        assert!(result.is_ok(), "{:?}", result);
+
+        let parquet_file = temp_csv.with_extension("parquet");
+        let file = std::fs::File::open(&parquet_file).unwrap();
+        let mut reader = parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder::try_new(file)
+            .unwrap()
+            .build()
+            .unwrap();
+        let batch = reader.next().unwrap().unwrap();
+        assert_eq!(batch.schema().field(1).data_type(), &DataType::Boolean);
+        assert!(batch.column(0).is_null(1));
+        assert!(batch.column(2).is_null(0));
+
+        let active = batch.column(1).as_any().downcast_ref::<arrow_array::BooleanArray>().unwrap();
+        assert!(active.value(0));
+        assert!(!active.value(1));
+        let note = batch.column(2).as_any().downcast_ref::<arrow_array::StringArray>().unwrap();
+        assert_eq!(note.value(1), "kept");
+
+        // Clean up
+        let _ = fs::remove_file(temp_csv);
+        let _ = fs::remove_file(parquet_file);
+    }
+
+    #[tokio::test]
+    async fn test_convert_to_parquet_with_date_formats() {
+        let mut temp_csv = std::env::temp_dir();
+        temp_csv.push("temp_sample_for_date_formats_test.csv");
+        fs::write(
+            &temp_csv,
+            "day,at,local\n16/10/2026,2026-10-16 12:00:00 +0300,2026-10-16 12:00:00\n,2026-10-16 09:00:00 +0000,2026-10-16T09:00:00Z\n",
+        )
+        .unwrap();
+
+        let options = ConvertOptions {
+            date_formats: vec!["%d/%m/%Y".to_string()],
+            timestamp_formats: vec!["%Y-%m-%d %H:%M:%S %z".to_string()],
+            timezone: Some("Europe/Istanbul".to_string()),
+            utc: true,
+            ..ConvertOptions::default()
+        };
+        let result = convert_to_parquet_with_options(&temp_csv, &options).await;
+        assert!(result.is_ok(), "{:?}", result);
+
+        let parquet_file = temp_csv.with_extension("parquet");
+        let file = std::fs::File::open(&parquet_file).unwrap();
+        let mut reader = parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder::try_new(file)
+            .unwrap()
+            .build()
+            .unwrap();
+        let batch = reader.next().unwrap().unwrap();
+        assert_eq!(batch.schema().field(0).data_type(), &DataType::Date32);
+        assert_eq!(
+            batch.schema().field(1).data_type(),
+            &DataType::Timestamp(arrow_schema::TimeUnit::Second, Some("UTC".into()))
+        );
+
+        let day = batch.column(0).as_any().downcast_ref::<arrow_array::Date32Array>().unwrap();
+        assert_eq!(day.value_as_date(0).unwrap().to_string(), "2026-10-16");
+        assert!(batch.column(0).is_null(1));
+
+        // Both timestamps are the same instant once normalized to UTC
+        for column in [1, 2] {
+            let at = batch
+                .column(column)
+                .as_any()
+                .downcast_ref::<arrow_array::TimestampSecondArray>()
+                .unwrap();
+            assert_eq!(at.value(0), at.value(1));
+            assert_eq!(at.value_as_datetime(0).unwrap().to_string(), "2026-10-16 09:00:00");
+        }
+
+        // Clean up
+        let _ = fs::remove_file(temp_csv);
+        let _ = fs::remove_file(parquet_file);
+    }
+
+    #[tokio::test]
+    async fn test_convert_to_parquet_with_decimals() {
+        let mut temp_csv = std::env::temp_dir();
+        temp_csv.push("temp_sample_for_decimals_test.csv");
+        fs::write(&temp_csv, "id,amount\n1,10.50\n2,-1234.5\n3,0.125\n").unwrap();
+
+        // The sampled values fit Decimal128(6, 2), the last one needs a third fractional digit
+        let options = ConvertOptions {
+            sampling_size: 2,
+            infer_decimals: true,
+            ..ConvertOptions::default()
+        };
+        let result = convert_to_parquet_with_options(&temp_csv, &options).await;
+        match result {
+            Err(Cc2pError::ValueError { line, expected, .. }) => {
+                assert_eq!(line, 4);
+                assert_eq!(expected, DataType::Decimal128(6, 2));
+            }
+            result => panic!("Unexpected result: {:?}", result),
+        }
+
+        let options = ConvertOptions {
+            inference: crate::inference::InferenceStrategy::Full,
+            ..options
+        };
+        let result = convert_to_parquet_with_options(&temp_csv, &options).await;
+        assert!(result.is_ok(), "{:?}", result);
+
+        let parquet_file = temp_csv.with_extension("parquet");
+        let file = std::fs::File::open(&parquet_file).unwrap();
+        let builder = parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder::try_new(file).unwrap();
+        let column = builder.metadata().file_metadata().schema_descr().column(1);
+        assert_eq!(column.converted_type(), parquet::basic::ConvertedType::DECIMAL);
+        assert_eq!((column.type_precision(), column.type_scale()), (7, 3));
+
+        let batch = builder.build().unwrap().next().unwrap().unwrap();
+        let amounts = batch.column(1).as_any().downcast_ref::<arrow_array::Decimal128Array>().unwrap();
+        assert_eq!(amounts.value_as_string(1), "-1234.500");
+
+        // Clean up
+        let _ = fs::remove_file(temp_csv);
+        let _ = fs::remove_file(parquet_file);
+    }
+
+    #[tokio::test]
+    async fn test_convert_to_parquet_with_number_locale() {
+        let mut temp_csv = std::env::temp_dir();
+        temp_csv.push("temp_sample_for_number_locale_test.csv");
+        fs::write(&temp_csv, "id;amount\n1;1.234,56 €\n2.000;-0,5 €\n").unwrap();
+
+        let options = ConvertOptions {
+            delimiter: ";".to_string(),
+            decimal_separator: ',',
+            grouping_separator: Some('.'),
+            strip_number_symbols: true,
+            ..ConvertOptions::default()
+        };
+        let result = convert_to_parquet_with_options(&temp_csv, &options).await;
+        assert!(result.is_ok(), "{:?}", result);
+
+        let parquet_file = temp_csv.with_extension("parquet");
+        let file = std::fs::File::open(&parquet_file).unwrap();
+        let mut reader = parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder::try_new(file)
+            .unwrap()
+            .build()
+            .unwrap();
+        let batch = reader.next().unwrap().unwrap();
+        let ids = batch.column(0).as_any().downcast_ref::<arrow_array::Int64Array>().unwrap();
+        assert_eq!(ids.values(), &[1, 2000]);
+        let amounts = batch.column(1).as_any().downcast_ref::<arrow_array::Float64Array>().unwrap();
+        assert_eq!(amounts.values(), &[1234.56, -0.5]);
+
+        // A mismatch reports the value as it is in the file, not the normalized one
+        fs::write(&temp_csv, "id;amount\n1;1.234,56 €\n2,5;-0,5 €\n").unwrap();
+        let options = ConvertOptions {
+            sampling_size: 1,
+            ..options
+        };
+        let result = convert_to_parquet_with_options(&temp_csv, &options).await;
+        match result {
+            Err(Cc2pError::ValueError { line, value, .. }) => {
+                assert_eq!(line, 3);
+                assert_eq!(value, "2,5");
+            }
+            result => panic!("Unexpected result: {:?}", result),
+        }
+
+        // Clean up
+        let _ = fs::remove_file(temp_csv);
+        let _ = fs::remove_file(parquet_file);
+    }
+
+    #[tokio::test]
+    async fn test_convert_to_parquet_with_multi_character_delimiter() {
+        let mut temp_csv = std::env::temp_dir();
+        temp_csv.push("temp_sample_for_multi_character_delimiter_test.csv");
+        fs::write(&temp_csv, "id||name\n1||\"a||b\"\n2||x|y\n").unwrap();
+
+        let options = ConvertOptions {
+            delimiter: "||".to_string(),
+            ..ConvertOptions::default()
+        };
+        let result = convert_to_parquet_with_options(&temp_csv, &options).await;
+        assert!(result.is_ok(), "{:?}", result);
+
+        let parquet_file = temp_csv.with_extension("parquet");
+        let file = std::fs::File::open(&parquet_file).unwrap();
+        let mut reader = parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder::try_new(file)
+            .unwrap()
+            .build()
+            .unwrap();
+        let batch = reader.next().unwrap().unwrap();
+        let ids = batch.column(0).as_any().downcast_ref::<arrow_array::Int64Array>().unwrap();
+        assert_eq!(ids.values(), &[1, 2]);
+        let names = batch.column(1).as_any().downcast_ref::<arrow_array::StringArray>().unwrap();
+        assert_eq!(names.value(0), "a||b");
+        assert_eq!(names.value(1), "x|y");
+
+        // Clean up
+        let _ = fs::remove_file(temp_csv);
+        let _ = fs::remove_file(parquet_file);
+    }
+
+    #[tokio::test]
+    async fn test_convert_to_parquet_with_encoding() {
+        let read_names = |path: &Path| {
+            let file = std::fs::File::open(path).unwrap();
+            let mut reader = parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder::try_new(file)
+                .unwrap()
+                .build()
+                .unwrap();
+            let batch = reader.next().unwrap().unwrap();
+            assert_eq!(batch.schema().field(0).name(), "id");
+            let names = batch.column(1).as_any().downcast_ref::<arrow_array::StringArray>().unwrap();
+            names.iter().map(|n| n.unwrap().to_string()).collect::<Vec<_>>()
+        };
+
+        let mut temp_csv = std::env::temp_dir();
+        temp_csv.push("temp_sample_for_encoding_test.csv");
+        let parquet_file = temp_csv.with_extension("parquet");
+
+        fs::write(&temp_csv, b"id,name\n1,Fran\xE7ois\n2,J\xE9r\xF4me\n").unwrap();
+        let options = ConvertOptions {
+            encoding: Some("windows-1252".to_string()),
+            ..ConvertOptions::default()
+        };
+        let result = convert_to_parquet_with_options(&temp_csv, &options).await;
+        assert!(result.is_ok(), "{:?}", result);
+        assert_eq!(read_names(&parquet_file), vec!["François", "Jérôme"]);
+        // The size is the one of the file, not of the longer UTF-8 text
+        assert_eq!(result.unwrap().input_bytes, fs::metadata(&temp_csv).unwrap().len());
+
+        // UTF-16 with a byte order mark is detected without --encoding
+        let utf16: Vec<u8> = "\u{feff}id;name\n1;Zoë\n".encode_utf16().flat_map(|c| c.to_le_bytes()).collect();
+        fs::write(&temp_csv, utf16).unwrap();
+        let options = ConvertOptions {
+            auto_dialect: true,
+            ..ConvertOptions::default()
+        };
+        let result = convert_to_parquet_with_options(&temp_csv, &options).await;
+        assert!(result.is_ok(), "{:?}", result);
+        assert_eq!(read_names(&parquet_file), vec!["Zoë"]);
+
+        // Clean up
+        let _ = fs::remove_file(temp_csv);
+        let _ = fs::remove_file(parquet_file);
+    }
+
+    #[tokio::test]
+    async fn test_convert_to_parquet_with_compressed_input() {
+        use std::io::Write;
+
+        let mut temp_csv = std::env::temp_dir();
+        temp_csv.push("temp_sample_for_compressed_input_test.csv.gz");
+        let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+        encoder.write_all(b"id;name\n1;a\n2;b\n").unwrap();
+        fs::write(&temp_csv, encoder.finish().unwrap()).unwrap();
+
+        let options = ConvertOptions {
+            auto_dialect: true,
+            ..ConvertOptions::default()
+        };
+        let (schema, _) = infer_schema_with_report(&temp_csv, &options).unwrap();
+        assert_eq!(schema.field(0).data_type(), &DataType::Int64);
+
+        let stats = convert_to_parquet_with_options(&temp_csv, &options).await.unwrap();
+        assert_eq!(stats.rows_written, 2);
+
+        let parquet_file = std::env::temp_dir().join("temp_sample_for_compressed_input_test.parquet");
+        assert!(parquet_file.exists());
+
+        // Clean up
+        let _ = fs::remove_file(temp_csv);
+        let _ = fs::remove_file(parquet_file);
+    }
+
+    #[tokio::test]
+    async fn test_convert_archive_members() {
+        use std::io::Write;
+
+        let dir = std::env::temp_dir().join("cc2p_archive_members_test");
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        let archive = dir.join("drop.zip");
+        let mut writer = zip::ZipWriter::new(fs::File::create(&archive).unwrap());
+        for (name, content) in [("sales/jan.csv", "id,amount\n1,2.5\n2,3.5\n"), ("feb.csv", "id,amount\n1,oops,3\n")] {
+            writer.start_file(name, zip::write::SimpleFileOptions::default()).unwrap();
+            writer.write_all(content.as_bytes()).unwrap();
+        }
+        writer.finish().unwrap();
+
+        let files = crate::utils::find_files(&dir.join("*.zip").to_string_lossy()).unwrap();
+        assert_eq!(files, vec![archive.join("sales/jan.csv"), archive.join("feb.csv")]);
+
+        let stats = convert_to_parquet_with_options(&files[0], &ConvertOptions::default())
+            .await
+            .unwrap();
+        assert_eq!(stats.rows_written, 2);
+        assert!(dir.join("drop").join("sales").join("jan.parquet").exists());
+
+        // Every member fails or succeeds on its own
+        let result = convert_to_parquet_with_options(&files[1], &ConvertOptions::default()).await;
+        assert!(matches!(result, Err(Cc2pError::RecordError { .. })), "{:?}", result);
+
+        // Clean up
+        let _ = fs::remove_dir_all(dir);
+    }
+
+    #[tokio::test]
+    async fn test_convert_tar_members() {
+        let dir = std::env::temp_dir().join("cc2p_tar_members_test");
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        let archive = dir.join("drop.tar");
+        let mut builder = tar::Builder::new(fs::File::create(&archive).unwrap());
+        for (name, content) in [("sales/jan.csv", "id,amount\n1,2.5\n2,3.5\n"), ("feb.csv", "id,amount\n1,oops,3\n")] {
+            let mut header = tar::Header::new_gnu();
+            header.set_size(content.len() as u64);
+            header.set_mode(0o644);
+            header.set_cksum();
+            builder.append_data(&mut header, name, content.as_bytes()).unwrap();
+        }
+        builder.finish().unwrap();
+
+        // The members are converted in the order of the arguments, whatever their order in the archive
+        let files = vec![archive.join("feb.csv"), archive.join("missing.csv"), archive.join("sales/jan.csv")];
+        let results = convert_tar_members(&archive, &files, &ConvertOptions::default()).await;
+        assert!(matches!(results[0].0, Err(Cc2pError::RecordError { .. })), "{:?}", results[0].0);
+        assert!(matches!(results[1].0, Err(Cc2pError::FileError(_))), "{:?}", results[1].0);
+        assert_eq!(results[2].0.as_ref().unwrap().rows_written, 2);
+        assert!(dir.join("drop").join("sales").join("jan.parquet").exists());
+
+        // Clean up
+        let _ = fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn test_target_path() {
+        let options = ConvertOptions::default();
+        assert_eq!(
+            target_path(Path::new("data/jan.csv.gz"), &options).unwrap(),
+            PathBuf::from("data/jan.parquet")
+        );
+        assert!(target_path(Path::new("-"), &options).is_err());
+
+        let options = ConvertOptions {
+            output: Some(PathBuf::from("-")),
+            output_dir: Some(PathBuf::from("ignored")),
+            ..ConvertOptions::default()
+        };
+        assert_eq!(target_path(Path::new("-"), &options).unwrap(), PathBuf::from("-"));
+
+        // `{parent}/` takes the place of the directory of the CSV file instead of repeating it
+        let options = ConvertOptions {
+            output_template: Some("{parent}/{stem}.parquet".to_string()),
+            ..ConvertOptions::default()
+        };
+        assert_eq!(
+            target_path(Path::new("data/2026/10/jan.csv"), &options).unwrap(),
+            PathBuf::from("data/2026/10/jan.parquet")
+        );
+        assert_eq!(target_path(Path::new("jan.csv"), &options).unwrap(), PathBuf::from("jan.parquet"));
+
+        let options = ConvertOptions {
+            output_template: Some("{parent}_{stem}.parquet".to_string()),
+            ..ConvertOptions::default()
+        };
+        assert_eq!(
+            target_path(Path::new("data/2026/10/jan.csv"), &options).unwrap(),
+            PathBuf::from("data/2026/10/10_jan.parquet")
+        );
+
+        // Only a leading `{parent}/` replaces the directory
+        let options = ConvertOptions {
+            output_template: Some("{stem}/{parent}/x.parquet".to_string()),
+            ..ConvertOptions::default()
+        };
+        assert_eq!(
+            target_path(Path::new("data/2026/10/jan.csv"), &options).unwrap(),
+            PathBuf::from("data/2026/10/jan/10/x.parquet")
+        );
+
+        let options = ConvertOptions {
+            output_dir: Some(PathBuf::from("out")),
+            input_root: Some(PathBuf::from("data")),
+            output_template: Some("{parent}/{stem}.parquet".to_string()),
+            ..ConvertOptions::default()
+        };
+        assert_eq!(
+            target_path(Path::new("data/2026/10/jan.csv"), &options).unwrap(),
+            PathBuf::from("out/2026/10/jan.parquet")
+        );
+
+        // Files outside of the input root are written directly into the output directory, in the directory that
+        // `{parent}/` names
+        assert_eq!(
+            target_path(Path::new("other/jan.csv"), &options).unwrap(),
+            PathBuf::from("out/other/jan.parquet")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_convert_to_parquet_with_overwrite() {
+        let dir = std::env::temp_dir().join("cc2p_overwrite_test");
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        let csv = dir.join("data.csv");
+        let target = dir.join("data.parquet");
+        fs::write(&csv, "id\n1\n").unwrap();
+        fs::write(&target, "previous").unwrap();
+
+        let mut options = ConvertOptions {
+            overwrite: OverwritePolicy::Never,
+            ..ConvertOptions::default()
+        };
+        let result = convert_to_parquet_with_options(&csv, &options).await;
+        assert!(matches!(result, Err(Cc2pError::OutputExists(_))));
+        assert_eq!(fs::read(&target).unwrap(), b"previous");
+
+        // The Parquet file was written after the CSV file
+        options.overwrite = OverwritePolicy::IfNewer;
+        assert!(convert_to_parquet_with_options(&csv, &options).await.is_err());
+
+        // A failed conversion keeps the previous file and removes the partial one
+        options.overwrite = OverwritePolicy::Always;
+        fs::write(&csv, "id\n1\n2,3\n").unwrap();
+        assert!(convert_to_parquet_with_options(&csv, &options).await.is_err());
+        assert_eq!(fs::read(&target).unwrap(), b"previous");
+        assert_eq!(fs::read_dir(&dir).unwrap().count(), 2);
+
+        fs::write(&csv, "id\n1\n2\n").unwrap();
+        let stats = convert_to_parquet_with_options(&csv, &options).await.unwrap();
+        assert_eq!(stats.rows_written, 2);
+        assert_eq!(fs::metadata(&target).unwrap().len(), stats.output_bytes);
+        assert_eq!(fs::read_dir(&dir).unwrap().count(), 2);
+
+        let old = std::time::SystemTime::UNIX_EPOCH + Duration::from_secs(86400);
+        fs::File::options().write(true).open(&target).unwrap().set_modified(old).unwrap();
+        options.overwrite = OverwritePolicy::IfNewer;
+        assert!(convert_to_parquet_with_options(&csv, &options).await.is_ok());
+
+        // Clean up
+        let _ = fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn test_persist() {
+        let dir = std::env::temp_dir().join("cc2p_persist_test");
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        let target = dir.join("data.parquet");
+        fs::write(&target, "previous").unwrap();
+
+        // Conversions with the same target write to different files
+        let partial = PartialFile(partial_path(&target));
+        assert_ne!(partial.0, partial_path(&target));
+        fs::write(&partial.0, "new").unwrap();
+
+        // The target appeared after it was checked
+        let result = persist(&partial.0, &target, OverwritePolicy::Never);
+        assert!(matches!(result, Err(Cc2pError::OutputExists(_))), "{:?}", result);
+        assert_eq!(fs::read(&target).unwrap(), b"previous");
+
+        persist(&partial.0, &target, OverwritePolicy::Always).unwrap();
+        assert_eq!(fs::read(&target).unwrap(), b"new");
+        drop(partial);
+        assert_eq!(fs::read_dir(&dir).unwrap().count(), 1);
+
+        // Clean up
+        let _ = fs::remove_dir_all(dir);
+    }
+
+    #[tokio::test]
+    async fn test_convert_to_parquet_with_output_dir() {
+        let dir = std::env::temp_dir().join("cc2p_output_dir_test");
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(dir.join("in/2026")).unwrap();
+        fs::write(dir.join("in/2026/jan.csv"), "id\n1\n").unwrap();
+
+        let pattern = dir.join("in/**/*.csv").to_string_lossy().into_owned();
+        let options = ConvertOptions {
+            output_dir: Some(dir.join("out")),
+            input_root: Some(crate::utils::glob_base(&pattern)),
+            output_template: Some("{stem}.{compression}.parquet".to_string()),
+            ..ConvertOptions::default()
+        };
+        let files = crate::utils::find_files(&pattern).unwrap();
+        assert_eq!(files.len(), 1);
+        convert_to_parquet_with_options(&files[0], &options).await.unwrap();
+        assert!(dir.join("out/2026/jan.snappy.parquet").exists());
+        assert!(!dir.join("in/2026/jan.parquet").exists());
+
+        // Clean up
+        let _ = fs::remove_dir_all(dir);
+    }
+
+    #[tokio::test]
+    async fn test_convert_to_parquet_with_auto_dialect() {
+        let mut temp_csv = std::env::temp_dir();
+        temp_csv.push("temp_sample_for_auto_dialect_test.csv");
+        fs::copy("testdata/sample_delimiter.csv", &temp_csv).unwrap();
+
+        let options = ConvertOptions {
+            auto_dialect: true,
+            ..ConvertOptions::default()
+        };
+        let stats = convert_to_parquet_with_options(&temp_csv, &options).await.unwrap();
+        let dialect = stats.dialect.unwrap();
+        assert_eq!(dialect.delimiter, ';');
+        assert!(dialect.has_header);
+        assert_eq!(stats.schema.fields().len(), 3);
+        assert_eq!(stats.schema.field(1).data_type(), &DataType::Int64);
+
+        // Clean up
+        let _ = fs::remove_file(temp_csv.with_extension("parquet"));
+        let _ = fs::remove_file(temp_csv);
+    }
+
+    #[tokio::test]
+    async fn test_convert_to_parquet_with_bad_rows() {
+        let mut temp_csv = std::env::temp_dir();
+        temp_csv.push("temp_sample_for_bad_rows_test.csv");
+        fs::write(&temp_csv, "id,amount\n1,10\n2,abc\n3,30,extra\n4,40\n").unwrap();
+        let parquet_file = temp_csv.with_extension("parquet");
+        let rejects_file = rejects_path(&parquet_file);
+
+        // Inference on the first row alone types "amount" as Int64
+        let options = ConvertOptions {
+            sampling_size: 1,
+            on_error: OnError::Skip,
+            ..ConvertOptions::default()
+        };
+        let result = convert_to_parquet_with_options(&temp_csv, &options).await;
+        assert!(result.is_ok(), "{:?}", result);
+        let file = std::fs::File::open(&parquet_file).unwrap();
+        let reader = parquet::file::reader::SerializedFileReader::new(file).unwrap();
+        assert_eq!(reader.metadata().file_metadata().num_rows(), 2);
+        assert!(!rejects_file.exists());
+
+        let options = ConvertOptions {
+            on_error: OnError::Quarantine,
+            ..options
+        };
+        let result = convert_to_parquet_with_options(&temp_csv, &options).await;
+        assert!(result.is_ok(), "{:?}", result);
+        let mut rejects = csv::Reader::from_path(&rejects_file).unwrap();
+        let rows: Vec<csv::StringRecord> = rejects.records().map(|r| r.unwrap()).collect();
+        assert_eq!(rows.len(), 2);
+        assert_eq!(&rows[0][0], "3");
+        assert_eq!(&rows[0][1], "cannot parse value 'abc' of column 'amount' as Int64");
+        assert_eq!(&rows[0][2], "2,abc");
+        assert_eq!(&rows[1][0], "4");
+        assert_eq!(&rows[1][1], "found a row with 3 fields, expected 2 fields");
+        assert_eq!(&rows[1][2], "3,30,extra");
+
+        // Exceeding the threshold fails the file
+        let options = ConvertOptions {
+            on_error: OnError::Skip,
+            max_bad_rows: Some(1),
+            ..options
+        };
+        let result = convert_to_parquet_with_options(&temp_csv, &options).await;
+        assert!(
+            matches!(result, Err(Cc2pError::TooManyBadRows { limit: 1, line: 4, .. })),
+            "{:?}",
+            result
+        );
+        // The Parquet file of the previous conversion is kept, with its rejects
+        let file = std::fs::File::open(&parquet_file).unwrap();
+        let reader = parquet::file::reader::SerializedFileReader::new(file).unwrap();
+        assert_eq!(reader.metadata().file_metadata().num_rows(), 2);
+        assert_eq!(csv::Reader::from_path(&rejects_file).unwrap().records().count(), 2);
+
+        // Clean up
+        let _ = fs::remove_file(temp_csv);
+        let _ = fs::remove_file(parquet_file);
+        let _ = fs::remove_file(rejects_file);
+    }
+
+    #[tokio::test]
+    async fn test_convert_to_parquet_with_failed_quarantine() {
+        let dir = std::env::temp_dir().join("cc2p_failed_quarantine_test");
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        let csv = dir.join("data.csv");
+        fs::write(&csv, "id\n1\n2,x\n3\n4,y\n").unwrap();
+
+        // The first bad row is quarantined before the second one fails the conversion
+        let options = ConvertOptions {
+            on_error: OnError::Quarantine,
+            max_bad_rows: Some(1),
+            ..ConvertOptions::default()
+        };
+        let result = convert_to_parquet_with_options(&csv, &options).await;
+        assert!(matches!(result, Err(Cc2pError::TooManyBadRows { .. })), "{:?}", result);
+        assert_eq!(fs::read_dir(&dir).unwrap().count(), 1);
+
+        // Clean up
+        let _ = fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn test_export_schema() {
+        let content = export_schema(
+            Path::new("testdata/sample_empty_header.csv"),
+            &ConvertOptions::default(),
+            SchemaFormat::Toml,
+        )
+        .unwrap();
+        let schema = crate::schema::parse_schema(&content, SchemaFormat::Toml).unwrap();
+
+        assert_eq!(schema.fields().len(), 3);
+        assert_eq!(schema.field(1).name(), "column_1");
+        assert_eq!(schema.field(1).data_type(), &DataType::Int64);
+    }
+
+    #[test]
+    fn test_infer_schema_with_replaced_schema() {
+        let schema = Schema::new(vec![
+            Field::new("first", DataType::Utf8, true),
+            Field::new("second", DataType::Int32, true),
+            Field::new("third", DataType::Utf8, true),
+        ]);
+        let options = ConvertOptions {
+            schema: Some(Arc::new(schema.clone())),
+            schema_mode: SchemaMode::Replace,
+            ..ConvertOptions::default()
+        };
+
+        assert_eq!(
+            infer_schema_with_options(Path::new("testdata/sample.csv"), &options).unwrap(),
+            schema
+        );
+    }
 }
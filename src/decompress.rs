@@ -0,0 +1,120 @@
+use bzip2::read::MultiBzDecoder;
+use flate2::read::MultiGzDecoder;
+use liblzma::read::XzDecoder;
+use std::io::{self, BufRead, BufReader, Read};
+use std::path::Path;
+
+/// Extensions of compressed CSV files, e.g. `data.csv.gz`.
+pub(crate) const COMPRESSED_EXTENSIONS: [&str; 4] = ["gz", "zst", "bz2", "xz"];
+
+/// Compression formats of CSV files that are decompressed while they are read.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum InputCompression {
+    Gzip,
+    Zstd,
+    Bzip2,
+    Xz,
+}
+
+impl InputCompression {
+    /// Returns the format of a file extension such as `gz`, matched case-insensitively.
+    pub(crate) fn from_extension(extension: &str) -> Option<Self> {
+        match extension.to_ascii_lowercase().as_str() {
+            "gz" => Some(InputCompression::Gzip),
+            "zst" => Some(InputCompression::Zstd),
+            "bz2" => Some(InputCompression::Bzip2),
+            "xz" => Some(InputCompression::Xz),
+            _ => None,
+        }
+    }
+
+    /// Detects the format from the magic bytes at the start of a file.
+    pub(crate) fn from_magic(head: &[u8]) -> Option<Self> {
+        if head.starts_with(&[0x1F, 0x8B]) {
+            Some(InputCompression::Gzip)
+        } else if head.starts_with(&[0x28, 0xB5, 0x2F, 0xFD]) {
+            Some(InputCompression::Zstd)
+        } else if head.starts_with(&[0xFD, b'7', b'z', b'X', b'Z', 0x00]) {
+            Some(InputCompression::Xz)
+        } else if head.len() >= 10
+            && head.starts_with(b"BZh")
+            && (b'1'..=b'9').contains(&head[3])
+            && (head[4..10] == [0x31, 0x41, 0x59, 0x26, 0x53, 0x59] || head[4..10] == [0x17, 0x72, 0x45, 0x38, 0x50, 0x90])
+        {
+            // "BZh" alone is a plausible start of a CSV file, so the block or end of stream magic must follow
+            Some(InputCompression::Bzip2)
+        } else {
+            None
+        }
+    }
+}
+
+/// Wraps a reader so that it yields the decompressed bytes of a compressed CSV file.
+///
+/// The format is detected from the magic bytes, falling back to the extension of `path`. Readers of other files
+/// are returned unchanged.
+///
+/// # Arguments
+///
+/// * `reader` - The reader of the file.
+/// * `path` - The path of the file.
+pub(crate) fn decompress_reader<'a, R: Read + 'a>(reader: R, path: &Path) -> io::Result<Box<dyn Read + 'a>> {
+    let mut reader = BufReader::new(reader);
+    let compression = InputCompression::from_magic(reader.fill_buf()?)
+        .or_else(|| path.extension().and_then(|e| e.to_str()).and_then(InputCompression::from_extension));
+
+    Ok(match compression {
+        None => Box::new(reader),
+        Some(InputCompression::Gzip) => Box::new(MultiGzDecoder::new(reader)),
+        Some(InputCompression::Zstd) => Box::new(zstd::Decoder::with_buffer(reader)?),
+        Some(InputCompression::Bzip2) => Box::new(MultiBzDecoder::new(reader)),
+        Some(InputCompression::Xz) => Box::new(XzDecoder::new_multi_decoder(reader)),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    const CSV: &str = "id,name\n1,a\n2,b\n";
+
+    fn decompress(bytes: Vec<u8>, path: &str) -> String {
+        let mut content = String::new();
+        decompress_reader(io::Cursor::new(bytes), Path::new(path))
+            .unwrap()
+            .read_to_string(&mut content)
+            .unwrap();
+        content
+    }
+
+    #[test]
+    fn test_decompress_reader() {
+        let mut gzip = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+        gzip.write_all(CSV.as_bytes()).unwrap();
+        assert_eq!(decompress(gzip.finish().unwrap(), "data.csv.gz"), CSV);
+
+        let zstd = zstd::encode_all(CSV.as_bytes(), 0).unwrap();
+        assert_eq!(decompress(zstd, "data.csv"), CSV);
+
+        let mut bzip2 = bzip2::write::BzEncoder::new(Vec::new(), bzip2::Compression::default());
+        bzip2.write_all(CSV.as_bytes()).unwrap();
+        assert_eq!(decompress(bzip2.finish().unwrap(), "data.bin"), CSV);
+
+        let mut xz = liblzma::write::XzEncoder::new(Vec::new(), 6);
+        xz.write_all(CSV.as_bytes()).unwrap();
+        assert_eq!(decompress(xz.finish().unwrap(), "data.csv.xz"), CSV);
+
+        assert_eq!(decompress(CSV.as_bytes().to_vec(), "data.csv"), CSV);
+        assert_eq!(decompress(b"BZh,name\n".to_vec(), "data.csv"), "BZh,name\n");
+    }
+
+    #[test]
+    fn test_corrupt_input() {
+        let mut content = Vec::new();
+        let result = decompress_reader(io::Cursor::new(CSV.as_bytes().to_vec()), Path::new("data.csv.gz"))
+            .unwrap()
+            .read_to_end(&mut content);
+        assert!(result.is_err());
+    }
+}
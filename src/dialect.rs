@@ -0,0 +1,266 @@
+use crate::error::{Cc2pError, Result};
+use crate::inference::value_type;
+use crate::options::ConvertOptions;
+use crate::reader::{ReadPass, Source, open_text, with_source};
+use arrow_schema::DataType;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::fmt;
+use std::io::Read;
+use std::path::Path;
+
+/// Number of bytes read from the head of a file to detect its dialect.
+const SNIFF_SIZE: u64 = 64 * 1024;
+
+/// Maximum number of records of the head used to detect the dialect.
+const SNIFF_RECORDS: usize = 100;
+
+/// Delimiters tried by the dialect detection, in order of preference.
+const DELIMITERS: [char; 4] = [',', ';', '\t', '|'];
+
+/// Quote characters tried by the dialect detection, in order of preference.
+const QUOTES: [char; 2] = ['"', '\''];
+
+/// Line terminators of a CSV file.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LineTerminator {
+    /// `\n`
+    Lf,
+    /// `\r\n`
+    Crlf,
+    /// `\r`
+    Cr,
+}
+
+/// The CSV dialect detected in the head of a file.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+pub struct Dialect {
+    /// The field delimiter.
+    pub delimiter: char,
+
+    /// The quote character.
+    pub quote: char,
+
+    /// Whether the first row is a header.
+    pub has_header: bool,
+
+    /// The line terminator.
+    pub terminator: LineTerminator,
+}
+
+impl Dialect {
+    /// Applies the dialect to a copy of the options and turns off `auto_dialect`.
+    pub fn apply(&self, options: &ConvertOptions) -> ConvertOptions {
+        ConvertOptions {
+            delimiter: self.delimiter.to_string(),
+            quote: self.quote,
+            has_header: self.has_header,
+            terminator: None,
+            auto_dialect: false,
+            ..options.clone()
+        }
+    }
+}
+
+impl fmt::Display for Dialect {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let delimiter = match self.delimiter {
+            '\t' => "tab".to_string(),
+            c => c.to_string(),
+        };
+        let terminator = match self.terminator {
+            LineTerminator::Lf => "LF",
+            LineTerminator::Crlf => "CRLF",
+            LineTerminator::Cr => "CR",
+        };
+        let header = if self.has_header { "header" } else { "no header" };
+        write!(f, "{} {} {} {}", delimiter, self.quote, header, terminator)
+    }
+}
+
+/// Detects the dialect of a CSV file from its head.
+///
+/// The delimiter (`,`, `;`, tab or `|`) and quote character (`"` or `'`) are the pair that splits the most
+/// records into the same number of fields. The first row is taken as a header unless its values have the same
+/// types as the values below them, e.g. when every column is numeric.
+///
+/// # Arguments
+///
+/// * `file_path` - The path of the CSV file.
+/// * `options` - The options whose dialect is kept when the file has a single column.
+///
+/// # Returns
+///
+/// Returns the detected dialect, or a `FileError` if the file cannot be read.
+///
+/// # Examples
+///
+/// ```rust
+/// use cc2p::dialect::sniff_dialect;
+/// use cc2p::options::ConvertOptions;
+/// use std::path::Path;
+///
+/// let dialect = sniff_dialect(Path::new("testdata/sample_delimiter.csv"), &ConvertOptions::default()).unwrap();
+/// assert_eq!(dialect.delimiter, ';');
+/// assert!(dialect.has_header);
+/// ```
+pub fn sniff_dialect(file_path: &Path, options: &ConvertOptions) -> Result<Dialect> {
+    with_source(file_path, |source| sniff_source(file_path, source, options))
+}
+
+/// Detects the dialect of a CSV file read from `source`, see [`sniff_dialect`].
+pub(crate) fn sniff_source(file_path: &Path, source: Source, options: &ConvertOptions) -> Result<Dialect> {
+    let mut head = Vec::new();
+    open_text(file_path, source, options, ReadPass::Sample)?
+        .take(SNIFF_SIZE)
+        .read_to_end(&mut head)
+        .map_err(Cc2pError::FileError)?;
+
+    // Drop the last line if it was cut off
+    if head.len() as u64 == SNIFF_SIZE
+        && let Some(end) = head.iter().rposition(|&b| b == b'\n' || b == b'\r')
+    {
+        head.truncate(end + 1);
+    }
+
+    Ok(sniff_head(&head, options))
+}
+
+/// Detects the dialect of the head of a CSV file.
+fn sniff_head(head: &[u8], options: &ConvertOptions) -> Dialect {
+    let mut best: Option<(usize, usize, char, char)> = None;
+    for delimiter in DELIMITERS {
+        for quote in QUOTES {
+            let (consistent, fields) = score(head, delimiter, quote);
+            if fields > 1 && best.is_none_or(|(c, f, _, _)| (consistent, fields) > (c, f)) {
+                best = Some((consistent, fields, delimiter, quote));
+            }
+        }
+    }
+    // Without a better candidate keep the configured delimiter, unless the sniffer cannot read it
+    let fallback = match options.delimiter_sequence() {
+        None => options.delimiter_byte() as char,
+        Some(_) => ',',
+    };
+    let (delimiter, quote) = best.map_or((fallback, options.quote), |(_, _, d, q)| (d, q));
+
+    let terminator = if head.windows(2).any(|w| w == b"\r\n") {
+        LineTerminator::Crlf
+    } else if head.contains(&b'\n') || !head.contains(&b'\r') {
+        LineTerminator::Lf
+    } else {
+        LineTerminator::Cr
+    };
+
+    Dialect {
+        delimiter,
+        quote,
+        has_header: has_header(&read_records(head, delimiter, quote)),
+        terminator,
+    }
+}
+
+/// Reads the records of the head with the given delimiter and quote, stopping at the first error.
+fn read_records(head: &[u8], delimiter: char, quote: char) -> Vec<csv::StringRecord> {
+    csv::ReaderBuilder::new()
+        .delimiter(delimiter as u8)
+        .quote(quote as u8)
+        .has_headers(false)
+        .flexible(true)
+        .from_reader(head)
+        .records()
+        .take(SNIFF_RECORDS)
+        .map_while(|record| record.ok())
+        .collect()
+}
+
+/// Scores a delimiter and quote pair.
+///
+/// # Returns
+///
+/// Returns the number of records that have the most common number of fields, and that number of fields.
+fn score(head: &[u8], delimiter: char, quote: char) -> (usize, usize) {
+    let mut counts: HashMap<usize, usize> = HashMap::new();
+    for record in read_records(head, delimiter, quote) {
+        *counts.entry(record.len()).or_default() += 1;
+    }
+
+    counts.into_iter().map(|(fields, count)| (count, fields)).max().unwrap_or_default()
+}
+
+/// Decides whether the first record is a header by comparing the type of its values with the values below.
+///
+/// Each column whose values below the first row share a single non-text type votes: for a header if the first
+/// value has a different type, against it otherwise. Ties count as a header.
+fn has_header(records: &[csv::StringRecord]) -> bool {
+    let Some((first, rest)) = records.split_first() else { return true };
+
+    let mut votes = 0i32;
+    for (i, value) in first.iter().enumerate() {
+        let mut types = rest.iter().filter_map(|r| r.get(i)).filter(|v| !v.is_empty()).map(value_type);
+        let Some(column_type) = types.next() else { continue };
+        if column_type == DataType::Utf8 || types.any(|t| t != column_type) {
+            continue;
+        }
+
+        if value_type(value) == column_type {
+            votes -= 1;
+        } else {
+            votes += 1;
+        }
+    }
+    votes >= 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sniff(head: &str) -> Dialect {
+        sniff_head(head.as_bytes(), &ConvertOptions::default())
+    }
+
+    #[test]
+    fn test_sniff_delimiter_and_quote() {
+        let dialect = sniff("id;name;amount\r\n1;\"a;b\";2,5\r\n2;c;3\r\n");
+        assert_eq!(dialect.delimiter, ';');
+        assert_eq!(dialect.quote, '"');
+        assert_eq!(dialect.terminator, LineTerminator::Crlf);
+
+        assert_eq!(sniff("id\tname\n1\tit's\n2\tb\n").delimiter, '\t');
+        assert_eq!(sniff("id|name\n1|a,b\n2|c\n").delimiter, '|');
+        assert_eq!(sniff("id,name\n1,'a,b'\n2,'c'\n").quote, '\'');
+    }
+
+    #[test]
+    fn test_sniff_header() {
+        assert!(sniff("id,amount\n1,2.5\n2,3.5\n").has_header);
+        assert!(!sniff("1,2.5\n2,3.5\n3,4.5\n").has_header);
+        assert!(!sniff("2026-10-16,abc\n2026-10-17,def\n").has_header);
+        assert!(sniff("name,city\nJohn,Paris\n").has_header);
+    }
+
+    #[test]
+    fn test_sniff_single_column() {
+        let options = ConvertOptions {
+            delimiter: "#".to_string(),
+            ..ConvertOptions::default()
+        };
+        let dialect = sniff_head(b"name\nJohn\n", &options);
+        assert_eq!(dialect.delimiter, '#');
+        assert_eq!(dialect.terminator, LineTerminator::Lf);
+
+        let options = ConvertOptions {
+            delimiter: "||".to_string(),
+            ..ConvertOptions::default()
+        };
+        assert_eq!(sniff_head(b"name\nJohn\n", &options).delimiter, ',');
+    }
+
+    #[test]
+    fn test_dialect_display() {
+        let dialect = sniff("a\tb\r\n1\t2\r\n");
+        assert_eq!(dialect.to_string(), "tab \" header CRLF");
+    }
+}
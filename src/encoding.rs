@@ -0,0 +1,98 @@
+use crate::options::ConvertOptions;
+use chardetng::{EncodingDetector, Iso2022JpDetection, Utf8Detection};
+use encoding_rs::{Encoding, UTF_8};
+use encoding_rs_io::DecodeReaderBytesBuilder;
+use std::io::{self, Cursor, Read};
+
+/// The encoding label that guesses the encoding of every file.
+pub(crate) const AUTO_ENCODING: &str = "auto";
+
+/// Number of bytes used to guess the encoding of a file.
+const DETECT_SIZE: u64 = 64 * 1024;
+
+/// Looks up a text encoding by its label, e.g. `windows-1252`, `latin1` or `utf-16le`.
+///
+/// The labels are those of the WHATWG Encoding Standard, matched case-insensitively.
+pub(crate) fn encoding_for_label(label: &str) -> Option<&'static Encoding> {
+    Encoding::for_label(label.trim().as_bytes())
+}
+
+/// Wraps a reader so that it yields UTF-8, transcoding from the encoding of the options.
+///
+/// A byte order mark always takes precedence over the configured encoding and is removed, so it never ends up in
+/// the first header name. UTF-8 input is passed through unchanged; other encodings replace invalid bytes with
+/// U+FFFD.
+pub(crate) fn decode_reader<'a, R: Read + 'a>(mut reader: R, options: &ConvertOptions) -> io::Result<Box<dyn Read + 'a>> {
+    let mut builder = DecodeReaderBytesBuilder::new();
+    builder.strip_bom(true).bom_override(true).utf8_passthru(true);
+
+    match options.encoding.as_deref() {
+        Some(label) if label.eq_ignore_ascii_case(AUTO_ENCODING) => {
+            let mut head = Vec::new();
+            (&mut reader).take(DETECT_SIZE).read_to_end(&mut head)?;
+            builder.encoding(detect_encoding(&head).filter(|&e| e != UTF_8));
+            Ok(Box::new(builder.build(Cursor::new(head).chain(reader))))
+        }
+        label => {
+            builder.encoding(label.and_then(encoding_for_label).filter(|&e| e != UTF_8));
+            Ok(Box::new(builder.build(reader)))
+        }
+    }
+}
+
+/// Guesses the encoding of the head of a file.
+///
+/// Returns `None` if the head starts with a byte order mark, which is detected while reading.
+fn detect_encoding(head: &[u8]) -> Option<&'static Encoding> {
+    if Encoding::for_bom(head).is_some() {
+        return None;
+    }
+
+    // A multi-byte character may be cut off at the end of the head
+    match std::str::from_utf8(head) {
+        Ok(_) => return Some(UTF_8),
+        Err(e) if e.error_len().is_none() && head.len() as u64 == DETECT_SIZE => return Some(UTF_8),
+        Err(_) => {}
+    }
+
+    let mut detector = EncodingDetector::new(Iso2022JpDetection::Deny);
+    detector.feed(head, true);
+    Some(detector.guess(None, Utf8Detection::Allow))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn decode(bytes: &'static [u8], encoding: Option<&str>) -> String {
+        let options = ConvertOptions {
+            encoding: encoding.map(|e| e.to_string()),
+            ..ConvertOptions::default()
+        };
+        let mut content = String::new();
+        decode_reader(bytes, &options).unwrap().read_to_string(&mut content).unwrap();
+        content
+    }
+
+    #[test]
+    fn test_decode_reader() {
+        assert_eq!(decode(b"\xEF\xBB\xBFid,name\n", None), "id,name\n");
+        assert_eq!(decode(b"\xFF\xFEi\0d\0\n\0", None), "id\n");
+        assert_eq!(decode(b"\xFE\xFF\0i\0d\0\n", Some("windows-1252")), "id\n");
+        assert_eq!(decode(b"caf\xE9 \x80\n", Some("windows-1252")), "café €\n");
+        assert_eq!(decode(b"caf\xE9\n", Some("latin1")), "café\n");
+        assert_eq!(decode(b"i\0d\0\n\0", Some("utf-16le")), "id\n");
+        assert_eq!(decode("café\n".as_bytes(), Some("utf-8")), "café\n");
+    }
+
+    #[test]
+    fn test_detect_encoding() {
+        assert_eq!(detect_encoding(b"\xEF\xBB\xBFid"), None);
+        assert_eq!(detect_encoding("id,name\n1,café\n".as_bytes()), Some(UTF_8));
+        assert_eq!(
+            detect_encoding(b"id,name,city\n1,Fran\xE7ois,Besan\xE7on\n2,J\xE9r\xF4me,Orl\xE9ans\n"),
+            Some(encoding_rs::WINDOWS_1252)
+        );
+        assert_eq!(decode(b"name\nJ\xE9r\xF4me Fran\xE7ois\n", Some("auto")), "name\nJérôme François\n");
+    }
+}
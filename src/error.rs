@@ -1,3 +1,5 @@
+use arrow_schema::DataType;
+use std::path::PathBuf;
 use thiserror::Error;
 
 /// Custom error types for the cc2p library.
@@ -11,6 +13,43 @@ pub enum Cc2pError {
     #[error("CSV parsing error: {0}")]
     CsvError(String),
 
+    /// Error that occurs when a value cannot be parsed as the type of its column.
+    #[error("{}:{line}: cannot parse value '{value}' of column '{column}' as {expected}", .path.display())]
+    ValueError {
+        /// The path of the CSV file.
+        path: PathBuf,
+        /// The 1-based line of the value in the CSV file.
+        line: u64,
+        /// The name of the column.
+        column: String,
+        /// The raw value.
+        value: String,
+        /// The type the value was expected to have.
+        expected: DataType,
+    },
+
+    /// Error that occurs when a row of a CSV file is malformed, e.g. it has the wrong number of fields.
+    #[error("{}:{line}: {message}", .path.display())]
+    RecordError {
+        /// The path of the CSV file.
+        path: PathBuf,
+        /// The 1-based line of the row in the CSV file.
+        line: u64,
+        /// What is wrong with the row.
+        message: String,
+    },
+
+    /// Error that occurs when more rows than `max_bad_rows` are skipped or quarantined.
+    #[error("more than {limit} bad rows, the last one at line {line}: {message}")]
+    TooManyBadRows {
+        /// The maximum number of bad rows.
+        limit: usize,
+        /// The 1-based line of the bad row that exceeded the limit.
+        line: u64,
+        /// Why that row was rejected.
+        message: String,
+    },
+
     /// Error that occurs when Parquet writing fails.
     #[error("Parquet writing error: {0}")]
     ParquetError(String),
@@ -23,11 +62,46 @@ pub enum Cc2pError {
     #[error("Pattern matching error: {0}")]
     PatternError(String),
 
+    /// Error that occurs when the Parquet file already exists and the overwrite policy keeps it.
+    #[error("Output file {} already exists", .0.display())]
+    OutputExists(PathBuf),
+
+    /// Error that occurs when a conversion option is invalid.
+    #[error("Configuration error: {0}")]
+    ConfigError(String),
+
     /// A generic error type for other errors.
     #[error("Other error: {0}")]
     Other(String),
 }
 
+impl Cc2pError {
+    /// Returns a short, stable name for the kind of error, e.g. `value` for [`Cc2pError::ValueError`].
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use cc2p::error::Cc2pError;
+    ///
+    /// assert_eq!(Cc2pError::ConfigError("invalid level".to_string()).kind(), "config");
+    /// ```
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Cc2pError::FileError(_) => "file",
+            Cc2pError::CsvError(_) => "csv",
+            Cc2pError::ValueError { .. } => "value",
+            Cc2pError::RecordError { .. } => "record",
+            Cc2pError::TooManyBadRows { .. } => "bad_rows",
+            Cc2pError::ParquetError(_) => "parquet",
+            Cc2pError::SchemaError(_) => "schema",
+            Cc2pError::PatternError(_) => "pattern",
+            Cc2pError::OutputExists(_) => "exists",
+            Cc2pError::ConfigError(_) => "config",
+            Cc2pError::Other(_) => "other",
+        }
+    }
+}
+
 /// Result type alias for cc2p operations.
 pub type Result<T> = std::result::Result<T, Cc2pError>;
 
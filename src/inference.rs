@@ -0,0 +1,787 @@
+use crate::error::{Cc2pError, Result};
+use crate::number::normalize_number;
+use crate::options::{ConvertOptions, OnError};
+use crate::reader::{ReadPass, Source, is_record_error, open_input, record_reader, to_read_error, with_source};
+use crate::temporal::{parse_date, parse_timestamp};
+use crate::utils::is_stdio;
+use arrow_csv::reader::Format;
+use arrow_schema::{DataType, Field, Schema, TimeUnit};
+use csv::StringRecord;
+use std::borrow::Cow;
+use std::fmt;
+use std::io::Read;
+use std::path::Path;
+
+/// Number of sampled rows handed to arrow-csv's schema inference at once.
+const INFERENCE_CHUNK_ROWS: usize = 1024;
+
+/// Maximum precision of a Decimal128 column.
+const DECIMAL128_MAX_PRECISION: u8 = 38;
+
+/// Strategies for selecting the rows used to infer the schema.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum InferenceStrategy {
+    /// The first `sampling_size` rows of the file.
+    #[default]
+    Head,
+    /// Every row of the file.
+    Full,
+    /// `sampling_size` rows picked at random across the whole file.
+    Random,
+    /// `sampling_size` rows evenly spread across the whole file.
+    Stratified,
+}
+
+/// A value that forced the inferred type of a column to a wider type.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TypeWidening {
+    /// The 1-based line of the value in the CSV file.
+    pub line: u64,
+
+    /// The name of the column.
+    pub column: String,
+
+    /// The type inferred before the value was seen.
+    pub from: DataType,
+
+    /// The type inferred after the value was seen.
+    pub to: DataType,
+
+    /// The value that forced the widening.
+    pub value: String,
+}
+
+impl fmt::Display for TypeWidening {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "line {}: column '{}' widened from {} to {} by value '{}'",
+            self.line, self.column, self.from, self.to, self.value
+        )
+    }
+}
+
+/// Details about a schema inference run.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct InferenceReport {
+    /// Number of data rows read from the file.
+    pub rows_read: usize,
+
+    /// Number of data rows used to infer the types.
+    pub rows_sampled: usize,
+
+    /// Values that forced a column to a wider type, in file order.
+    pub widenings: Vec<TypeWidening>,
+}
+
+/// Infers the type of every column of `records` with arrow-csv, the same reader that decodes the values later.
+///
+/// The records are written in the standard CSV dialect, so values are typed exactly as arrow-csv types them.
+/// Empty values are null.
+fn infer_types<'r>(records: impl IntoIterator<Item = &'r StringRecord>) -> Result<Vec<DataType>> {
+    let mut writer = csv::WriterBuilder::new().has_headers(false).from_writer(Vec::new());
+    for record in records {
+        writer.write_record(record).map_err(|e| Cc2pError::SchemaError(e.to_string()))?;
+    }
+    let data = writer.into_inner().map_err(|e| Cc2pError::SchemaError(e.to_string()))?;
+
+    let (schema, _) = Format::default()
+        .with_header(false)
+        .infer_schema(data.as_slice(), None)
+        .map_err(|e| Cc2pError::SchemaError(e.to_string()))?;
+    Ok(schema.fields().iter().map(|f| f.data_type().clone()).collect())
+}
+
+/// Infers the type of a single column holding `values`, see [`infer_types`].
+fn infer_column<'v>(values: impl IntoIterator<Item = &'v str>) -> Result<DataType> {
+    let records: Vec<StringRecord> = values.into_iter().map(|value| StringRecord::from(vec![value])).collect();
+    Ok(infer_types(&records)?.pop().unwrap_or(DataType::Null))
+}
+
+/// Returns the narrowest type of a single non-null value.
+pub(crate) fn value_type(value: &str) -> DataType {
+    infer_column([value]).unwrap_or(DataType::Utf8)
+}
+
+/// Returns a value arrow-csv infers as `data_type`, or `None` for types it never infers.
+fn type_sample(data_type: &DataType) -> Option<&'static str> {
+    match data_type {
+        DataType::Boolean => Some("true"),
+        DataType::Int8 | DataType::Int16 | DataType::Int32 | DataType::Int64 => Some("0"),
+        DataType::UInt8 | DataType::UInt16 | DataType::UInt32 | DataType::UInt64 => Some("0"),
+        DataType::Float16 | DataType::Float32 | DataType::Float64 => Some("0.5"),
+        DataType::Decimal128(_, _) | DataType::Decimal256(_, _) => Some("0.5"),
+        DataType::Date32 | DataType::Date64 => Some("1970-01-01"),
+        DataType::Timestamp(TimeUnit::Second, None) => Some("1970-01-01T00:00:00"),
+        DataType::Timestamp(TimeUnit::Millisecond, None) => Some("1970-01-01T00:00:00.000"),
+        DataType::Timestamp(TimeUnit::Microsecond, None) => Some("1970-01-01T00:00:00.000000"),
+        DataType::Timestamp(TimeUnit::Nanosecond, None) => Some("1970-01-01T00:00:00.000000000"),
+        _ => None,
+    }
+}
+
+/// Returns the type a column has to be widened to so it can hold `value`.
+///
+/// The wider type is the type arrow-csv infers for a column holding both kinds of values, e.g. Int64 or
+/// Decimal128 to Float64 to Utf8 and Date32 to Timestamp to Utf8. Types arrow-csv does not infer, and values
+/// whose type was already accepted, widen to Utf8.
+///
+/// # Arguments
+///
+/// * `current` - The current type of the column.
+/// * `value` - The value that could not be parsed as `current`.
+///
+/// # Returns
+///
+/// Returns the wider type, or `None` if the column cannot be widened any further.
+pub(crate) fn widen_type(current: &DataType, value: &str) -> Option<DataType> {
+    if value.is_empty() || current == &DataType::Utf8 {
+        return None;
+    }
+
+    let widened = match type_sample(current) {
+        Some(sample) => infer_column([sample, value]).unwrap_or(DataType::Utf8),
+        None => DataType::Utf8,
+    };
+    match widened {
+        widened if &widened == current => Some(DataType::Utf8),
+        widened => Some(widened),
+    }
+}
+
+/// Rewrites a sampled value into the form arrow-csv infers, applying the null, boolean, number and date
+/// settings of `options`. Null values become empty.
+fn sample_value<'a>(value: &'a str, options: &ConvertOptions) -> Cow<'a, str> {
+    if options.is_null_value(value) {
+        return Cow::Borrowed("");
+    }
+
+    let normalized = options
+        .boolean_value(value)
+        .map(|boolean| boolean.to_string())
+        .or_else(|| normalize_number(value, options))
+        .or_else(|| parse_date(value, options))
+        .or_else(|| parse_timestamp(value, options));
+    normalized.map_or(Cow::Borrowed(value), Cow::Owned)
+}
+
+/// The digits of the numbers in a column, so a Float64 column of fixed-point numbers can be typed as a decimal.
+#[derive(Clone, Copy, Debug, Default)]
+struct Digits {
+    integer_digits: u8,
+    scale: u8,
+    not_fixed_point: bool,
+}
+
+impl Digits {
+    /// Adds the digits of a value.
+    fn update(&mut self, value: &str) {
+        let digits = value.strip_prefix('-').unwrap_or(value);
+        // Exponents, NaN and infinity cannot be written as decimals
+        if !digits.bytes().all(|b| b.is_ascii_digit() || b == b'.') {
+            self.not_fixed_point = true;
+            return;
+        }
+
+        let (integer, fraction) = digits.split_once('.').unwrap_or((digits, ""));
+        let integer_digits = integer.trim_start_matches('0').len();
+        self.integer_digits = self.integer_digits.max(u8::try_from(integer_digits).unwrap_or(u8::MAX));
+        self.scale = self.scale.max(u8::try_from(fraction.len()).unwrap_or(u8::MAX));
+    }
+
+    /// Returns the narrowest decimal type that can hold all numbers seen so far, if they are all fixed-point
+    /// numbers and at least one of them has a fractional part.
+    fn decimal_type(&self) -> Option<DataType> {
+        if self.not_fixed_point || self.scale == 0 {
+            return None;
+        }
+
+        let precision = self.integer_digits.saturating_add(self.scale).max(1);
+        (precision <= DECIMAL128_MAX_PRECISION).then_some(DataType::Decimal128(precision, self.scale as i8))
+    }
+}
+
+/// The type inferred for a column so far.
+struct InferredColumn {
+    data_type: DataType,
+    /// The values that widened the column, which together have the type of every value seen so far.
+    widened_by: Vec<String>,
+    digits: Digits,
+}
+
+/// A sampled row waiting to be typed by arrow-csv.
+struct SampledRow {
+    line: u64,
+    raw: StringRecord,
+    normalized: StringRecord,
+}
+
+/// Accumulates the column types of the sampled rows.
+///
+/// The rows are typed by arrow-csv in chunks. Only when a chunk widens a column are its values typed one by one,
+/// to find the rows that forced the widening.
+struct Inferrer<'a> {
+    headers: Vec<String>,
+    columns: Vec<InferredColumn>,
+    pending: Vec<SampledRow>,
+    report: InferenceReport,
+    options: &'a ConvertOptions,
+}
+
+impl<'a> Inferrer<'a> {
+    fn new(headers: Vec<String>, options: &'a ConvertOptions) -> Self {
+        Inferrer {
+            columns: (0..headers.len())
+                .map(|_| InferredColumn {
+                    data_type: DataType::Null,
+                    widened_by: Vec::new(),
+                    digits: Digits::default(),
+                })
+                .collect(),
+            headers,
+            pending: Vec::with_capacity(INFERENCE_CHUNK_ROWS),
+            report: InferenceReport::default(),
+            options,
+        }
+    }
+
+    fn update(&mut self, record: &StringRecord) -> Result<()> {
+        self.report.rows_sampled += 1;
+
+        let normalized: StringRecord = record.iter().map(|value| sample_value(value, self.options)).collect();
+        for (column, value) in self.columns.iter_mut().zip(normalized.iter()) {
+            if !value.is_empty() {
+                column.digits.update(value);
+            }
+        }
+        self.pending.push(SampledRow {
+            line: record.position().map_or(0, |p| p.line()),
+            raw: record.clone(),
+            normalized,
+        });
+
+        if self.pending.len() == INFERENCE_CHUNK_ROWS {
+            self.infer_pending()?;
+        }
+        Ok(())
+    }
+
+    /// Types the pending rows and records the values that widened a column.
+    fn infer_pending(&mut self) -> Result<()> {
+        if self.pending.is_empty() {
+            return Ok(());
+        }
+
+        let types = infer_types(self.pending.iter().map(|row| &row.normalized))?;
+        let mut widenings = Vec::new();
+        for (i, (column, chunk_type)) in self.columns.iter_mut().zip(types).enumerate() {
+            if chunk_type == DataType::Null || chunk_type == column.data_type {
+                continue;
+            }
+            let values = || self.pending.iter().map(|row| &row.normalized[i]).filter(|v| !v.is_empty());
+            let known = column.widened_by.iter().map(String::as_str);
+            if infer_column(known.chain(values()))? == column.data_type {
+                continue;
+            }
+
+            for row in &self.pending {
+                let value = &row.normalized[i];
+                if value.is_empty() {
+                    continue;
+                }
+                let known = column.widened_by.iter().map(String::as_str);
+                let widened = infer_column(known.chain([value]))?;
+                if widened == column.data_type {
+                    continue;
+                }
+
+                if column.data_type != DataType::Null {
+                    widenings.push(TypeWidening {
+                        line: row.line,
+                        column: self.headers[i].clone(),
+                        from: column.data_type.clone(),
+                        to: widened.clone(),
+                        value: row.raw[i].to_string(),
+                    });
+                }
+                column.widened_by.push(value.to_string());
+                column.data_type = widened;
+            }
+        }
+
+        widenings.sort_by_key(|widening| widening.line);
+        self.report.widenings.append(&mut widenings);
+        self.pending.clear();
+        Ok(())
+    }
+
+    fn finish(mut self) -> Result<(Schema, InferenceReport)> {
+        self.infer_pending()?;
+
+        // Since only a sample may have been read, every column is assumed to be nullable
+        let fields: Vec<Field> = self
+            .headers
+            .iter()
+            .zip(&self.columns)
+            .map(|(name, column)| match &column.data_type {
+                DataType::Timestamp(unit, _) => Field::new(name, self.options.timestamp_type(*unit), true),
+                DataType::Float64
+                    if self.options.infer_decimals
+                        && let Some(decimal) = column.digits.decimal_type() =>
+                {
+                    Field::new(name, decimal, true)
+                }
+                data_type => Field::new(name, data_type.clone(), true),
+            })
+            .collect();
+
+        Ok((Schema::new(fields), self.report))
+    }
+}
+
+/// Small deterministic pseudo random generator, so random samples are reproducible between runs.
+struct XorShift(u64);
+
+impl XorShift {
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+}
+
+/// Reads the next record, skipping malformed rows unless `options.on_error` fails on them.
+fn read_record<R: Read>(reader: &mut csv::Reader<R>, record: &mut StringRecord, file_path: &Path, options: &ConvertOptions) -> Result<bool> {
+    loop {
+        match reader.read_record(record) {
+            Ok(has_record) => return Ok(has_record),
+            Err(e) if options.on_error != OnError::Fail && is_record_error(&e) => continue,
+            Err(e) if is_record_error(&e) => return Err(to_read_error(e, file_path)),
+            Err(e) => return Err(Cc2pError::SchemaError(e.to_string())),
+        }
+    }
+}
+
+/// Infers the schema of a CSV file and reports which rows widened the column types.
+///
+/// The rows used for inference are selected by `options.inference`. The `Stratified` strategy reads the file
+/// twice: once to count the rows and once to sample them.
+///
+/// # Arguments
+///
+/// * `file_path` - The path of the CSV file.
+/// * `options` - The CSV reading options.
+///
+/// # Returns
+///
+/// Returns the inferred schema and the inference report, otherwise returns an error.
+pub fn infer_schema_from_file(file_path: &Path, options: &ConvertOptions) -> Result<(Schema, InferenceReport)> {
+    with_source(file_path, |source| infer_schema_from_source(file_path, source, options))
+}
+
+/// Infers the schema of a CSV file read from `source`, see [`infer_schema_from_file`].
+///
+/// A stream keeps every byte that inference reads in memory for the conversion, so it only supports the `Head`
+/// strategy.
+pub(crate) fn infer_schema_from_source(file_path: &Path, source: Source, options: &ConvertOptions) -> Result<(Schema, InferenceReport)> {
+    if matches!(source, Source::Stream(_)) && options.inference != InferenceStrategy::Head {
+        let name = if is_stdio(file_path) {
+            "stdin".into()
+        } else {
+            file_path.display().to_string()
+        };
+        return Err(Cc2pError::ConfigError(format!(
+            "{} can only be read once, use head inference or a schema instead of {:?} inference",
+            name, options.inference
+        )));
+    }
+
+    let sample_size = options.sampling_size;
+    let mut rows_read = 0;
+
+    let open =
+        || -> Result<csv::Reader<Box<dyn Read + '_>>> { record_reader(open_input(file_path, source, options, ReadPass::Sample)?, options) };
+    let read_error = |e: csv::Error| Cc2pError::SchemaError(e.to_string());
+
+    let mut reader = open()?;
+    let headers: Vec<String> = if options.has_header {
+        reader.headers().map_err(read_error)?.iter().map(|s| s.to_string()).collect()
+    } else {
+        let count = reader.headers().map_err(read_error)?.len();
+        (0..count).map(|i| format!("column_{}", i + 1)).collect()
+    };
+    let mut inferrer = Inferrer::new(headers, options);
+    let mut record = StringRecord::new();
+
+    match options.inference {
+        InferenceStrategy::Head | InferenceStrategy::Full => {
+            let limit = if options.inference == InferenceStrategy::Full {
+                usize::MAX
+            } else {
+                sample_size
+            };
+            while rows_read < limit && read_record(&mut reader, &mut record, file_path, options)? {
+                rows_read += 1;
+                inferrer.update(&record)?;
+            }
+        }
+        InferenceStrategy::Random => {
+            let mut rng = XorShift(0x9E37_79B9_7F4A_7C15);
+            let mut reservoir: Vec<StringRecord> = Vec::with_capacity(sample_size.min(64 * 1024));
+            while read_record(&mut reader, &mut record, file_path, options)? {
+                if reservoir.len() < sample_size {
+                    reservoir.push(record.clone());
+                } else {
+                    let j = (rng.next() % (rows_read as u64 + 1)) as usize;
+                    if j < sample_size {
+                        reservoir[j] = record.clone();
+                    }
+                }
+                rows_read += 1;
+            }
+
+            // Replay the sample in file order so the widening report reads top to bottom
+            reservoir.sort_by_key(|r| r.position().map_or(0, |p| p.line()));
+            for record in &reservoir {
+                inferrer.update(record)?;
+            }
+        }
+        InferenceStrategy::Stratified => {
+            let mut total = 0;
+            while read_record(&mut reader, &mut record, file_path, options)? {
+                total += 1;
+            }
+
+            let mut reader = open()?;
+            if options.has_header {
+                reader.headers().map_err(read_error)?;
+            }
+
+            let mut next_sample = 0;
+            while next_sample < sample_size.min(total) && read_record(&mut reader, &mut record, file_path, options)? {
+                // Pick the rows closest to `k * total / sample_size`
+                if rows_read == next_sample * total / sample_size.min(total) {
+                    inferrer.update(&record)?;
+                    next_sample += 1;
+                }
+                rows_read += 1;
+            }
+            rows_read = total;
+        }
+    }
+
+    let (schema, mut report) = inferrer.finish()?;
+    report.rows_read = rows_read;
+    Ok((schema, report))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::options::TimestampUnit;
+    use crate::replay::Replay;
+    use std::fs;
+
+    fn write_temp_csv(name: &str, content: &str) -> std::path::PathBuf {
+        let mut path = std::env::temp_dir();
+        path.push(name);
+        fs::write(&path, content).unwrap();
+        path
+    }
+
+    fn numbers_with_text_at_end(rows: usize) -> String {
+        let mut content = String::from("id,value\n");
+        for i in 0..rows {
+            content.push_str(&format!("{},{}\n", i, i));
+        }
+        content.push_str(&format!("{},text\n", rows));
+        content
+    }
+
+    #[test]
+    fn test_infer_column() {
+        let infer = |values: &[&str]| infer_column(values.iter().copied()).unwrap();
+
+        assert_eq!(infer(&[]), DataType::Null);
+        assert_eq!(infer(&["true", "FALSE"]), DataType::Boolean);
+        assert_eq!(infer(&["1", "-2"]), DataType::Int64);
+        assert_eq!(infer(&["1", "2.5"]), DataType::Float64);
+        assert_eq!(infer(&["2026-10-16"]), DataType::Date32);
+        assert_eq!(
+            infer(&["2026-10-16", "2026-10-16 12:00:00"]),
+            DataType::Timestamp(TimeUnit::Second, None)
+        );
+        assert_eq!(infer(&["1", "true"]), DataType::Utf8);
+        assert_eq!(infer(&["a,\"b\"", "1"]), DataType::Utf8);
+        assert_eq!(value_type("99999999999999999999"), DataType::Utf8);
+    }
+
+    #[test]
+    fn test_decimal_type() {
+        let decimal = |values: &[&str]| {
+            let mut digits = Digits::default();
+            values.iter().for_each(|v| digits.update(v));
+            digits.decimal_type()
+        };
+
+        assert_eq!(decimal(&["10.50", "-1234.5", "7"]), Some(DataType::Decimal128(6, 2)));
+        assert_eq!(decimal(&["0.001"]), Some(DataType::Decimal128(3, 3)));
+        assert_eq!(decimal(&["1", "2"]), None);
+        assert_eq!(decimal(&["1.5", "2e3"]), None);
+        assert_eq!(decimal(&["1.5", "NaN"]), None);
+        assert_eq!(decimal(&["12345678901234567890.123456789012345678901"]), None);
+    }
+
+    #[test]
+    fn test_widen_type() {
+        assert_eq!(widen_type(&DataType::Int64, "2.5"), Some(DataType::Float64));
+        assert_eq!(widen_type(&DataType::Int64, "abc"), Some(DataType::Utf8));
+        assert_eq!(widen_type(&DataType::Float64, "abc"), Some(DataType::Utf8));
+        assert_eq!(
+            widen_type(&DataType::Date32, "2026-10-16 12:00:00"),
+            Some(DataType::Timestamp(TimeUnit::Second, None))
+        );
+        assert_eq!(
+            widen_type(&DataType::Timestamp(TimeUnit::Second, None), "yesterday"),
+            Some(DataType::Utf8)
+        );
+        assert_eq!(widen_type(&DataType::Decimal128(10, 2), "1.234"), Some(DataType::Float64));
+        assert_eq!(widen_type(&DataType::Decimal128(10, 2), "abc"), Some(DataType::Utf8));
+        assert_eq!(widen_type(&DataType::Utf8, "abc"), None);
+        assert_eq!(widen_type(&DataType::Int64, ""), None);
+    }
+
+    #[test]
+    fn test_infer_head_and_full() {
+        let path = write_temp_csv("temp_inference_head_full.csv", &numbers_with_text_at_end(100));
+
+        let options = ConvertOptions::new(',', true, 10);
+        let (schema, report) = infer_schema_from_file(&path, &options).unwrap();
+        assert_eq!(schema.field(1).data_type(), &DataType::Int64);
+        assert_eq!(report.rows_read, 10);
+        assert!(report.widenings.is_empty());
+
+        let options = ConvertOptions {
+            inference: InferenceStrategy::Full,
+            ..ConvertOptions::new(',', true, 10)
+        };
+        let (schema, report) = infer_schema_from_file(&path, &options).unwrap();
+        assert_eq!(schema.field(0).data_type(), &DataType::Int64);
+        assert_eq!(schema.field(1).data_type(), &DataType::Utf8);
+        assert_eq!(report.rows_read, 101);
+        assert_eq!(report.rows_sampled, 101);
+        assert_eq!(
+            report.widenings,
+            vec![TypeWidening {
+                line: 102,
+                column: "value".to_string(),
+                from: DataType::Int64,
+                to: DataType::Utf8,
+                value: "text".to_string(),
+            }]
+        );
+
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_infer_widenings_across_chunks() {
+        let mut content = numbers_with_text_at_end(INFERENCE_CHUNK_ROWS + 10);
+        content.insert_str("id,value\n0,0\n".len(), "-1,0.5\n");
+        let path = write_temp_csv("temp_inference_widenings_chunks.csv", &content);
+
+        let options = ConvertOptions {
+            inference: InferenceStrategy::Full,
+            ..ConvertOptions::default()
+        };
+        let (schema, report) = infer_schema_from_file(&path, &options).unwrap();
+        assert_eq!(schema.field(0).data_type(), &DataType::Int64);
+        assert_eq!(schema.field(1).data_type(), &DataType::Utf8);
+        let widened: Vec<(u64, &DataType, &DataType)> = report.widenings.iter().map(|w| (w.line, &w.from, &w.to)).collect();
+        assert_eq!(
+            widened,
+            vec![
+                (3, &DataType::Int64, &DataType::Float64),
+                (INFERENCE_CHUNK_ROWS as u64 + 13, &DataType::Float64, &DataType::Utf8)
+            ]
+        );
+
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_infer_stratified() {
+        let path = write_temp_csv("temp_inference_stratified.csv", &numbers_with_text_at_end(99));
+
+        let options = ConvertOptions {
+            inference: InferenceStrategy::Stratified,
+            ..ConvertOptions::new(',', true, 10)
+        };
+        let (schema, report) = infer_schema_from_file(&path, &options).unwrap();
+        assert_eq!(report.rows_read, 100);
+        assert_eq!(report.rows_sampled, 10);
+        assert_eq!(schema.field(1).data_type(), &DataType::Int64);
+
+        // Sampling every row includes the last one
+        let options = ConvertOptions {
+            inference: InferenceStrategy::Stratified,
+            ..ConvertOptions::new(',', true, 500)
+        };
+        let (schema, report) = infer_schema_from_file(&path, &options).unwrap();
+        assert_eq!(report.rows_sampled, 100);
+        assert_eq!(schema.field(1).data_type(), &DataType::Utf8);
+
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_infer_random() {
+        let path = write_temp_csv("temp_inference_random.csv", &numbers_with_text_at_end(1000));
+
+        let options = ConvertOptions {
+            inference: InferenceStrategy::Random,
+            ..ConvertOptions::new(',', true, 50)
+        };
+        let (schema, report) = infer_schema_from_file(&path, &options).unwrap();
+        assert_eq!(report.rows_read, 1001);
+        assert_eq!(report.rows_sampled, 50);
+        assert_eq!(schema.field(0).data_type(), &DataType::Int64);
+
+        // The sample is deterministic
+        let (_, second_report) = infer_schema_from_file(&path, &options).unwrap();
+        assert_eq!(report, second_report);
+
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_infer_stream() {
+        let path = Path::new("-");
+        let replay = std::cell::RefCell::new(Replay::new(std::io::Cursor::new(numbers_with_text_at_end(100))));
+
+        let (schema, report) = infer_schema_from_source(path, Source::Stream(&replay), &ConvertOptions::new(',', true, 10)).unwrap();
+        assert_eq!(schema.field(1).data_type(), &DataType::Int64);
+        assert_eq!(report.rows_read, 10);
+
+        // Any other strategy would keep the whole stream in memory
+        let options = ConvertOptions {
+            inference: InferenceStrategy::Full,
+            ..ConvertOptions::default()
+        };
+        assert!(matches!(
+            infer_schema_from_source(path, Source::Stream(&replay), &options),
+            Err(Cc2pError::ConfigError(_))
+        ));
+    }
+
+    #[test]
+    fn test_infer_malformed_row() {
+        let path = write_temp_csv("temp_inference_malformed_row.csv", "id,value\n1,2\n3,4,5\n");
+
+        match infer_schema_from_file(&path, &ConvertOptions::default()) {
+            Err(Cc2pError::RecordError { line, .. }) => assert_eq!(line, 3),
+            result => panic!("Unexpected result: {:?}", result),
+        }
+
+        let options = ConvertOptions {
+            on_error: OnError::Skip,
+            ..ConvertOptions::default()
+        };
+        let (_, report) = infer_schema_from_file(&path, &options).unwrap();
+        assert_eq!(report.rows_read, 1);
+
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_infer_with_value_tokens() {
+        let path = write_temp_csv(
+            "temp_inference_value_tokens.csv",
+            "id, active ,score\n1,Y,NULL\n2 , n ,\\N\nN/A,y, 2.5\n",
+        );
+
+        let (schema, _) = infer_schema_from_file(&path, &ConvertOptions::default()).unwrap();
+        assert!(schema.fields().iter().all(|f| f.data_type() == &DataType::Utf8));
+
+        let options = ConvertOptions {
+            null_values: vec!["NULL".to_string(), "\\N".to_string(), "N/A".to_string()],
+            true_values: vec!["Y".to_string()],
+            false_values: vec!["N".to_string()],
+            trim: true,
+            ..ConvertOptions::default()
+        };
+        let (schema, _) = infer_schema_from_file(&path, &options).unwrap();
+        assert_eq!(schema.field(0).data_type(), &DataType::Int64);
+        assert_eq!(schema.field(1).name(), "active");
+        assert_eq!(schema.field(1).data_type(), &DataType::Boolean);
+        assert_eq!(schema.field(2).data_type(), &DataType::Float64);
+
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_infer_with_date_formats() {
+        let path = write_temp_csv(
+            "temp_inference_date_formats.csv",
+            "day,compact,at\n16/10/2026,20261016,2026-10-16 12:00:00 +0300\n17/10/2026,20261017,2026-10-17 08:30:00 +0300\n",
+        );
+
+        let options = ConvertOptions {
+            date_formats: vec!["%d/%m/%Y".to_string(), "%Y%m%d".to_string()],
+            timestamp_formats: vec!["%Y-%m-%d %H:%M:%S %z".to_string()],
+            timestamp_unit: Some(TimestampUnit::Millisecond),
+            utc: true,
+            ..ConvertOptions::default()
+        };
+        let (schema, _) = infer_schema_from_file(&path, &options).unwrap();
+        assert_eq!(schema.field(0).data_type(), &DataType::Date32);
+        assert_eq!(schema.field(1).data_type(), &DataType::Date32);
+        assert_eq!(
+            schema.field(2).data_type(),
+            &DataType::Timestamp(TimeUnit::Millisecond, Some("UTC".into()))
+        );
+
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_infer_with_number_locale() {
+        let path = write_temp_csv(
+            "temp_inference_number_locale.csv",
+            "id;amount;population;share\n1;1.234,56 €;1 234 567;12,5%\n2;-0,99 €;980;7%\n",
+        );
+
+        let options = ConvertOptions {
+            delimiter: ";".to_string(),
+            decimal_separator: ',',
+            grouping_separator: Some('.'),
+            strip_number_symbols: true,
+            ..ConvertOptions::default()
+        };
+        let (schema, _) = infer_schema_from_file(&path, &options).unwrap();
+        assert_eq!(schema.field(1).data_type(), &DataType::Float64);
+        assert_eq!(schema.field(2).data_type(), &DataType::Utf8);
+        assert_eq!(schema.field(3).data_type(), &DataType::Float64);
+
+        let options = ConvertOptions {
+            grouping_separator: Some(' '),
+            ..options
+        };
+        let (schema, _) = infer_schema_from_file(&path, &options).unwrap();
+        assert_eq!(schema.field(1).data_type(), &DataType::Utf8);
+        assert_eq!(schema.field(2).data_type(), &DataType::Int64);
+
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_infer_without_header() {
+        let options = ConvertOptions::new(',', false, 10);
+        let (schema, report) = infer_schema_from_file(Path::new("testdata/sample_no_header.csv"), &options).unwrap();
+
+        assert_eq!(schema.field(0).name(), "column_1");
+        assert_eq!(schema.field(1).data_type(), &DataType::Int64);
+        assert_eq!(report.rows_read, 5);
+    }
+}
@@ -6,11 +6,20 @@
 //! ## Features
 //!
 //! - Asynchronous file operations
-//! - Custom delimiters
-//! - Schema inference
+//! - Custom delimiters and dialects, or automatic dialect detection
+//! - Legacy text encodings such as Windows-1252 and UTF-16
+//! - Gzip, zstd, bzip2 and xz compressed CSV files
+//! - CSV files inside zip and tar archives
+//! - Schema inference with optional schema files and full-file scans
+//! - Optional type widening for values that do not match the inferred schema
+//! - Skipping or quarantining bad rows
+//! - Custom null, boolean, date and timestamp formats
 //! - Header detection
 //! - Duplicate column handling
 //! - Parallel processing
+//! - Configurable Parquet compression
+//! - Atomic replacement of existing Parquet files, or keeping them
+//! - Per-column encoding, dictionary and statistics settings
 //!
 //! ## Example
 //!
@@ -31,15 +40,37 @@
 //! }
 //! ```
 
+mod archive;
+pub mod config;
 pub mod conversion;
+mod decompress;
+pub mod dialect;
+mod encoding;
 pub mod error;
+pub mod inference;
+mod number;
+pub mod options;
+mod reader;
+pub mod rejects;
+mod replay;
+pub mod report;
+pub mod schema;
+mod temporal;
 pub mod tui;
 pub mod utils;
 
 // Re-export commonly used items
+pub use conversion::ConversionStats;
 pub use conversion::convert_to_parquet;
 pub use conversion::convert_to_parquet_with_columns;
+pub use conversion::convert_to_parquet_with_options;
+pub use conversion::export_schema;
 pub use conversion::infer_schema;
+pub use conversion::infer_schema_with_options;
+pub use conversion::infer_schema_with_report;
 pub use conversion::remove_deduplicate_columns;
+pub use options::CompressionCodec;
+pub use options::ConvertOptions;
+pub use schema::load_schema;
 pub use utils::clean_column_name;
 pub use utils::find_files;
@@ -1,15 +1,43 @@
 extern crate core;
 
+use std::collections::BTreeMap;
+use std::fmt;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use std::str::FromStr;
 use std::sync::Arc;
-use std::time::Instant;
+use std::time::{Duration, Instant};
 
-use clap::Parser;
+use clap::{Parser, Subcommand};
 use indicatif::{ProgressBar, ProgressStyle};
 use tokio::runtime;
 use tokio::sync::Mutex;
 
+use cc2p::config::load_config;
+use cc2p::conversion::convert_tar_members;
+use cc2p::error::Cc2pError;
+use cc2p::inference::InferenceStrategy;
+use cc2p::options::{ColumnEncoding, CompressionCodec, ConvertOptions, OnError, OverwritePolicy, SchemaMode, StatisticsLevel, TimestampUnit};
+use cc2p::report::{FileReport, RunReport};
+use cc2p::schema::{SchemaFormat, load_schema, schema_to_string};
 use cc2p::tui::run_tui;
-use cc2p::{convert_to_parquet, find_files};
+use cc2p::utils::{
+    FoundInput, STDIO, find_inputs, format_byte_size, glob_base, is_stdio, output_path, parse_byte_size, parse_delimiter, tar_archive,
+};
+use cc2p::{ConversionStats, convert_to_parquet_with_options, infer_schema_with_report, remove_deduplicate_columns};
+
+/// Exit code when every file was converted.
+const EXIT_SUCCESS: u8 = 0;
+
+/// Exit code when some, but not all, files failed.
+const EXIT_PARTIAL_FAILURE: u8 = 1;
+
+/// Exit code for invalid arguments, options or paths, the same code clap uses for usage errors.
+const EXIT_USAGE_ERROR: u8 = 2;
+
+/// Exit code when every file failed, or the run itself failed.
+const EXIT_TOTAL_FAILURE: u8 = 3;
 
 /// A command line parser for processing CSV files with specified parameters.
 ///
@@ -20,32 +48,159 @@ use cc2p::{convert_to_parquet, find_files};
 ///
 /// # Arguments
 ///
-/// * `path` - Represents the folder path for CSV search. Default value is "*.csv".
+/// * `path` - Represents the folder path for CSV search, or "-" for stdin. Default value is "*.csv".
+/// * `output` - Path of the Parquet file, or "-" for stdout, when a single file is converted.
+/// * `output_dir` - Directory the Parquet files are written to, mirroring the directories of the CSV files.
+/// * `output_template` - Name of the Parquet files, e.g. "{stem}_{date}.parquet".
+/// * `overwrite` - What happens when a Parquet file already exists: "always", "never" or "if-newer".
 /// * `delimiter` - Represents the delimiter used in CSV files. The default value is ",".
+/// * `encoding` - Text encoding of CSV files, or "auto" to guess it per file. The default value is UTF-8.
 /// * `no_header` - Represents whether to include the header in the CSV search column. The default value is `false`.
+/// * `auto` - Whether the dialect of every file is detected instead of using the dialect options.
+/// * `quote` - Quote character used in CSV files. The default value is '"'.
+/// * `escape` - Escape character for quotes inside quoted fields.
+/// * `no_double_quote` - Whether two adjacent quotes inside quoted fields are not read as one quote.
+/// * `comment` - Lines starting with this character are skipped.
+/// * `terminator` - Record terminator character.
 /// * `worker` - Number of worker threads to use for performing the task. Default value is 1.
 /// * `sampling` - Number of rows to sample for inferring the schema. The default value is 2048.
+/// * `inference` - How the rows used for inferring the schema are selected. The default value is "head".
+/// * `compression` - Compression codec used for the Parquet files. The default value is "snappy".
+/// * `compression_level` - Compression level for codecs that support one (gzip, brotli, zstd).
+/// * `batch_size` - Number of CSV rows decoded per record batch.
+/// * `row_group_rows` - Maximum number of rows in a Parquet row group.
+/// * `row_group_bytes` - Maximum size of a Parquet row group, e.g. "128MB".
+/// * `data_page_size` - Maximum size of a Parquet data page, e.g. "1MB".
+/// * `dictionary_page_size` - Maximum size of a Parquet dictionary page, e.g. "1MB".
+/// * `write_batch_size` - Number of values the Parquet writer processes per column write.
+/// * `config` - TOML configuration file with per-column writer settings.
+/// * `column_encoding` - Per-column encodings in the form `COLUMN=ENCODING`.
+/// * `column_dictionary` - Per-column dictionary settings in the form `COLUMN=true|false`.
+/// * `column_statistics` - Per-column statistics levels in the form `COLUMN=none|chunk|page`.
+/// * `bloom_filter` - Columns for which a bloom filter is written.
+/// * `bloom_filter_fpp` - False positive probability of the bloom filters.
+/// * `bloom_filter_ndv` - Expected number of distinct values per row group for the bloom filters.
+/// * `schema` - JSON or TOML schema file overriding the inferred schema.
+/// * `schema_mode` - How the schema file is applied: "override" or "replace".
+/// * `widen_types` - Whether columns are widened instead of failing when a value does not match the inferred type.
+/// * `on_error` - What happens with rows that cannot be read or converted: "fail", "skip" or "quarantine".
+/// * `max_bad_rows` - Maximum number of skipped or quarantined rows per file before the conversion fails.
+/// * `null_values` - Values read as null in addition to the empty value.
+/// * `true_values` - Values read as true in boolean columns.
+/// * `false_values` - Values read as false in boolean columns.
+/// * `trim` - Whether leading and trailing whitespace is removed from headers and values.
+/// * `date_formats` - chrono formats of dates, tried in order.
+/// * `timestamp_formats` - chrono formats of timestamps, tried in order.
+/// * `timestamp_unit` - Unit of the timestamp columns: "s", "ms", "us" or "ns".
+/// * `timezone` - Timezone of timestamps without an offset.
+/// * `utc` - Whether the timestamp columns are written in UTC.
+/// * `decimal_separator` - Decimal separator of numbers. The default value is ".".
+/// * `grouping_separator` - Grouping (thousands) separator of numbers.
+/// * `strip_number_symbols` - Whether currency and percent symbols around numbers are removed.
+/// * `infer_decimals` - Whether columns of fixed-point numbers are inferred as Decimal128 instead of Float64.
+/// * `report` - Path of a JSON summary of every converted file.
+/// * `command` - Optional subcommand, e.g. `schema` to print the inferred schema instead of converting.
 ///
 #[derive(Parser, Debug)]
-#[command(version, about, long_about = None)]
+#[command(version, about, long_about = None, args_conflicts_with_subcommands = true)]
 struct Args {
     /// Represents the folder path for CSV search.
-    #[arg(default_value_t = String::from("*.csv"), help = "Represents the folder path for CSV search.")]
+    #[arg(default_value_t = String::from("*.csv"), help = "Represents the folder path for CSV search, or - to read a CSV file from stdin.")]
     path: String,
 
+    /// Path of the Parquet file, or - for stdout.
+    #[arg(
+        short,
+        long,
+        help = "Path of the Parquet file, or - to write it to stdout. Needs a single input file."
+    )]
+    output: Option<PathBuf>,
+
+    /// Directory the Parquet files are written to.
+    #[arg(
+        long,
+        conflicts_with = "output",
+        help = "Directory the Parquet files are written to, mirroring the directories of the CSV files below the searched folder."
+    )]
+    output_dir: Option<PathBuf>,
+
+    /// Name of the Parquet files.
+    #[arg(
+        long,
+        conflicts_with = "output",
+        help = "Name of the Parquet files with the placeholders {stem}, {parent}, {date} and {compression}, e.g. {stem}_{date}.parquet or {parent}/{stem}.snappy.parquet. [default: {stem}.parquet]"
+    )]
+    output_template: Option<String>,
+
+    /// What happens when a Parquet file already exists.
+    #[arg(
+        long,
+        value_enum,
+        default_value_t = OverwritePolicy::Always,
+        help = "What happens when a Parquet file already exists: replace it, keep it and skip the CSV file, or replace it only if the CSV file is newer."
+    )]
+    overwrite: OverwritePolicy,
+
     /// Represents the delimiter used in CSV files.
-    #[arg(short, long, default_value_t = String::from(","), help = "Represents the delimiter used in CSV files.")]
+    #[arg(short, long, global = true, default_value_t = String::from(","), help = "Represents the delimiter used in CSV files: a character, a sequence such as || or §, an escape (\\t, \\x1F) or a name (tab, comma, semicolon, pipe, space).")]
     delimiter: String,
 
+    /// Text encoding of CSV files.
+    #[arg(
+        long,
+        global = true,
+        help = "Text encoding of CSV files, e.g. windows-1252, latin1 or utf-16le, or auto to guess it per file. A byte order mark takes precedence. [default: utf-8]"
+    )]
+    encoding: Option<String>,
+
     /// Represents whether to include the header in the CSV search column.
     #[arg(
         short,
         long,
+        global = true,
         default_value_t = false,
         help = "Indicates whether to include the header in the CSV search column."
     )]
     no_header: bool,
 
+    /// Detects the dialect of every file.
+    #[arg(
+        long,
+        global = true,
+        default_value_t = false,
+        help = "Detects the delimiter (, ; tab |), quote character, header and line terminator of every file, replacing --delimiter, --quote, --no-header and --terminator."
+    )]
+    auto: bool,
+
+    /// Quote character used in CSV files.
+    #[arg(long, global = true, default_value_t = '"', help = "Quote character used in CSV files.")]
+    quote: char,
+
+    /// Escape character for quotes inside quoted fields.
+    #[arg(
+        long,
+        global = true,
+        help = "Escape character for quotes inside quoted fields, e.g. '\\' for \\\". [default: none, quotes are doubled]"
+    )]
+    escape: Option<char>,
+
+    /// Disables reading two adjacent quotes as one quote inside quoted fields.
+    #[arg(
+        long,
+        global = true,
+        default_value_t = false,
+        help = "Disables reading two adjacent quotes as one quote inside quoted fields."
+    )]
+    no_double_quote: bool,
+
+    /// Lines starting with this character are skipped.
+    #[arg(long, global = true, help = "Skips lines starting with this character, e.g. '#'.")]
+    comment: Option<char>,
+
+    /// Record terminator.
+    #[arg(long, global = true, help = "Record terminator character. [default: \\n, \\r or \\r\\n]")]
+    terminator: Option<char>,
+
     /// Number of worker threads to use for performing the task.
     #[arg(
         short,
@@ -56,12 +211,305 @@ struct Args {
     worker: u8,
 
     /// Number of rows to sample for inferring the schema. The default value is 2048.
-    #[arg(short, long, default_value_t = 2048, help = "Number of rows to sample for inferring the schema.")]
-    sampling: u16,
+    #[arg(
+        short,
+        long,
+        global = true,
+        default_value_t = 2048,
+        help = "Number of rows to sample for inferring the schema."
+    )]
+    sampling: usize,
+
+    /// How the rows used for inferring the schema are selected.
+    #[arg(
+        long,
+        global = true,
+        value_enum,
+        default_value_t = InferenceStrategy::Head,
+        help = "How the rows used for inferring the schema are selected: the first rows, the full file, or a random or evenly spread sample of --sampling rows."
+    )]
+    inference: InferenceStrategy,
 
     /// Show an interactive UI.
     #[arg(short, long, default_value_t = false, help = "Show an interactive UI.")]
     interactive: bool,
+
+    /// Compression codec used for the Parquet files.
+    #[arg(short, long, value_enum, default_value_t = CompressionCodec::Snappy, help = "Compression codec used for the Parquet files.")]
+    compression: CompressionCodec,
+
+    /// Compression level for codecs that support one (gzip, brotli, zstd).
+    #[arg(
+        long,
+        help = "Compression level for codecs that support one (gzip: 0-9, brotli: 0-11, zstd: 1-22)."
+    )]
+    compression_level: Option<i32>,
+
+    /// Number of CSV rows decoded per record batch.
+    #[arg(long, help = "Number of CSV rows decoded per record batch. [default: 1024]")]
+    batch_size: Option<usize>,
+
+    /// Maximum number of rows in a Parquet row group.
+    #[arg(long, help = "Maximum number of rows in a Parquet row group. [default: 1048576]")]
+    row_group_rows: Option<usize>,
+
+    /// Maximum size of a Parquet row group.
+    #[arg(long, value_parser = parse_size_arg, help = "Maximum size of a Parquet row group, e.g. 128MB.")]
+    row_group_bytes: Option<usize>,
+
+    /// Maximum size of a Parquet data page.
+    #[arg(long, value_parser = parse_size_arg, help = "Maximum size of a Parquet data page, e.g. 1MB. [default: 1MB]")]
+    data_page_size: Option<usize>,
+
+    /// Maximum size of a Parquet dictionary page.
+    #[arg(long, value_parser = parse_size_arg, help = "Maximum size of a Parquet dictionary page, e.g. 1MB. [default: 1MB]")]
+    dictionary_page_size: Option<usize>,
+
+    /// Number of values the Parquet writer processes per column write.
+    #[arg(long, help = "Number of values the Parquet writer processes per column write. [default: 1024]")]
+    write_batch_size: Option<usize>,
+
+    /// TOML configuration file with per-column writer settings.
+    #[arg(long, help = "TOML configuration file with per-column writer settings.")]
+    config: Option<PathBuf>,
+
+    /// Per-column encodings in the form `COLUMN=ENCODING`.
+    #[arg(
+        long,
+        value_name = "COLUMN=ENCODING",
+        value_parser = parse_column_arg::<ColumnEncoding>,
+        help = "Encoding for a column: plain, delta_binary_packed, delta_length_byte_array, delta_byte_array, byte_stream_split or rle. Can be repeated."
+    )]
+    column_encoding: Vec<(String, ColumnEncoding)>,
+
+    /// Per-column dictionary settings in the form `COLUMN=true|false`.
+    #[arg(
+        long,
+        value_name = "COLUMN=BOOL",
+        value_parser = parse_column_arg::<bool>,
+        help = "Enables or disables dictionary encoding for a column. Can be repeated."
+    )]
+    column_dictionary: Vec<(String, bool)>,
+
+    /// Per-column statistics levels in the form `COLUMN=none|chunk|page`.
+    #[arg(
+        long,
+        value_name = "COLUMN=LEVEL",
+        value_parser = parse_column_arg::<StatisticsLevel>,
+        help = "Statistics level for a column: none, chunk or page. Can be repeated."
+    )]
+    column_statistics: Vec<(String, StatisticsLevel)>,
+
+    /// Columns for which a bloom filter is written.
+    #[arg(long, value_name = "COLUMN", help = "Writes a bloom filter for a column. Can be repeated.")]
+    bloom_filter: Vec<String>,
+
+    /// False positive probability of the bloom filters.
+    #[arg(long, help = "False positive probability of the bloom filters. [default: 0.05]")]
+    bloom_filter_fpp: Option<f64>,
+
+    /// Expected number of distinct values per row group for the bloom filters.
+    #[arg(long, help = "Expected number of distinct values per row group for the bloom filters.")]
+    bloom_filter_ndv: Option<u64>,
+
+    /// JSON or TOML schema file overriding the inferred schema.
+    #[arg(long, global = true, help = "JSON or TOML schema file overriding the inferred schema.")]
+    schema: Option<PathBuf>,
+
+    /// How the schema file is applied.
+    #[arg(long, global = true, value_enum, default_value_t = SchemaMode::Override, help = "How the schema file is applied.")]
+    schema_mode: SchemaMode,
+
+    /// Widens a column and retries instead of failing when a value does not match the inferred type.
+    #[arg(
+        long,
+        default_value_t = false,
+        help = "Widens a column (Int64 to Float64 to Utf8, Date to Timestamp to Utf8) and retries instead of failing when a value does not match the inferred type."
+    )]
+    widen_types: bool,
+
+    /// What happens with rows that cannot be read or converted.
+    #[arg(
+        long,
+        global = true,
+        value_enum,
+        default_value_t = OnError::Fail,
+        help = "What happens with rows that cannot be read or converted: fail the file, skip the row, or quarantine it in <name>.rejects.csv."
+    )]
+    on_error: OnError,
+
+    /// Maximum number of skipped or quarantined rows per file before the conversion fails.
+    #[arg(
+        long,
+        help = "Maximum number of skipped or quarantined rows per file before the conversion fails. [default: unlimited]"
+    )]
+    max_bad_rows: Option<usize>,
+
+    /// Values read as null, in addition to the empty value.
+    #[arg(
+        long,
+        global = true,
+        value_name = "VALUES",
+        value_delimiter = ',',
+        help = "Comma separated values read as null in addition to the empty value, e.g. NULL,\\N,N/A."
+    )]
+    null_values: Vec<String>,
+
+    /// Values read as true in boolean columns.
+    #[arg(
+        long,
+        global = true,
+        value_name = "VALUES",
+        value_delimiter = ',',
+        help = "Comma separated values read as true in boolean columns, e.g. Y,yes. Case-insensitive."
+    )]
+    true_values: Vec<String>,
+
+    /// Values read as false in boolean columns.
+    #[arg(
+        long,
+        global = true,
+        value_name = "VALUES",
+        value_delimiter = ',',
+        help = "Comma separated values read as false in boolean columns, e.g. N,no. Case-insensitive."
+    )]
+    false_values: Vec<String>,
+
+    /// Removes leading and trailing whitespace from headers and values.
+    #[arg(
+        long,
+        global = true,
+        default_value_t = false,
+        help = "Removes leading and trailing whitespace from headers and values."
+    )]
+    trim: bool,
+
+    /// Formats of dates, tried in order before ISO 8601.
+    #[arg(
+        long = "date-format",
+        global = true,
+        value_name = "FORMAT",
+        help = "chrono format of dates, e.g. %d/%m/%Y or %Y%m%d. Can be repeated, formats are tried in order."
+    )]
+    date_formats: Vec<String>,
+
+    /// Formats of timestamps, tried in order before ISO 8601.
+    #[arg(
+        long = "timestamp-format",
+        global = true,
+        value_name = "FORMAT",
+        help = "chrono format of timestamps, e.g. '%Y-%m-%d %H:%M:%S %z'. Can be repeated, formats are tried in order."
+    )]
+    timestamp_formats: Vec<String>,
+
+    /// Unit of the timestamp columns.
+    #[arg(
+        long,
+        global = true,
+        value_enum,
+        help = "Unit of the timestamp columns. [default: picked from the fractional seconds]"
+    )]
+    timestamp_unit: Option<TimestampUnit>,
+
+    /// Timezone of timestamps without an offset.
+    #[arg(
+        long,
+        global = true,
+        value_name = "TZ",
+        help = "Timezone of timestamps without an offset, e.g. Europe/Istanbul or +03:00. Timestamp columns keep this timezone unless --utc is set."
+    )]
+    timezone: Option<String>,
+
+    /// Writes the timestamp columns in UTC.
+    #[arg(long, global = true, default_value_t = false, help = "Writes the timestamp columns in UTC.")]
+    utc: bool,
+
+    /// Decimal separator of numbers.
+    #[arg(
+        long,
+        global = true,
+        default_value_t = '.',
+        help = "Decimal separator of numbers, e.g. ',' for 1234,56."
+    )]
+    decimal_separator: char,
+
+    /// Grouping (thousands) separator of numbers.
+    #[arg(
+        long,
+        global = true,
+        help = "Grouping (thousands) separator of numbers, e.g. '.' for 1.234,56 or ' ' for 1 234 567."
+    )]
+    grouping_separator: Option<char>,
+
+    /// Removes currency and percent symbols around numbers.
+    #[arg(
+        long,
+        global = true,
+        default_value_t = false,
+        help = "Removes currency and percent symbols around numbers, e.g. '€ 12,50' or '12%'."
+    )]
+    strip_number_symbols: bool,
+
+    /// Infers Decimal128 columns for fixed-point numbers.
+    #[arg(
+        long,
+        global = true,
+        default_value_t = false,
+        help = "Infers Decimal128(precision, scale) instead of Float64 for columns of fixed-point numbers, e.g. amounts."
+    )]
+    infer_decimals: bool,
+
+    /// Path of a JSON summary of every converted file.
+    #[arg(
+        long,
+        value_name = "PATH",
+        help = "Writes a JSON summary of every file (status, error kind, rows written, input/output bytes, duration) to PATH."
+    )]
+    report: Option<PathBuf>,
+
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+/// Subcommands that run instead of the conversion.
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Prints the inferred schema of CSV files in the format accepted by `--schema`.
+    Schema {
+        /// Represents the folder path for CSV search.
+        #[arg(default_value_t = String::from("*.csv"), help = "Represents the folder path for CSV search.")]
+        path: String,
+
+        /// Format of the schema file.
+        #[arg(short, long, value_enum, default_value_t = SchemaFormat::Json, help = "Format of the schema file.")]
+        format: SchemaFormat,
+
+        /// Writes the schema next to each CSV file instead of printing it.
+        #[arg(
+            long,
+            default_value_t = false,
+            help = "Writes the schema next to each CSV file as <name>.schema.<format> instead of printing it."
+        )]
+        write: bool,
+    },
+}
+
+/// Parses a per-column command line argument such as "id=delta_binary_packed".
+fn parse_column_arg<T>(value: &str) -> Result<(String, T), String>
+where
+    T: FromStr,
+    T::Err: std::fmt::Display,
+{
+    let (column, setting) = value
+        .rsplit_once('=')
+        .ok_or_else(|| format!("expected COLUMN=VALUE, got '{}'", value))?;
+    let setting = setting.trim().parse::<T>().map_err(|e| e.to_string())?;
+    Ok((column.to_string(), setting))
+}
+
+/// Parses a byte size command line argument such as "128MB".
+fn parse_size_arg(value: &str) -> Result<usize, String> {
+    parse_byte_size(value).map_err(|e| e.to_string())
 }
 
 /// A structure to hold error information related to CSV file processing.
@@ -73,58 +521,376 @@ struct Args {
 /// # Fields
 ///
 /// * `file_path` - The path of the CSV file where the error was encountered.
-/// * `error` - A description of the error that occurred in the given file.
+/// * `error` - The error that occurred in the given file.
 ///
 /// # Example
 ///
 /// ```
 /// let error_data = ErrorData {
 ///     file_path: String::from("data.csv"),
-///     error: String::from("Failed to open the file."),
+///     error: Cc2pError::Other(String::from("Failed to open the file.")),
 /// };
-/// println!("Error in file {}: {}", error_data.file_path, error_data.error);
+/// println!("{}", error_data);
 /// ```
 struct ErrorData {
     /// The path of the CSV file where the error was encountered.
     file_path: String,
 
-    /// A description of the error that occurred in the given file.
-    error: String,
+    /// The error that occurred in the given file.
+    error: Cc2pError,
+}
+
+/// The results of the conversion tasks, shared by all of them.
+#[derive(Clone)]
+struct Results {
+    errors: Arc<Mutex<Vec<ErrorData>>>,
+    reports: Arc<Mutex<Vec<FileReport>>>,
+    stats: Arc<Mutex<Vec<(String, ConversionStats)>>>,
+    skipped: Arc<Mutex<Vec<(String, PathBuf)>>>,
+    bar: Arc<Mutex<ProgressBar>>,
+}
+
+impl Results {
+    /// Records the result of converting a file and advances the progress bar.
+    async fn record(&self, file: &Path, result: cc2p::error::Result<ConversionStats>, elapsed: Duration) {
+        let report = FileReport::new(file, &result, elapsed);
+        self.reports.lock().await.push(report);
+
+        let file_path = file.to_str().unwrap_or("invalid path").to_string();
+        match result {
+            Ok(file_stats) => self.stats.lock().await.push((file_path, file_stats)),
+            Err(Cc2pError::OutputExists(target)) => self.skipped.lock().await.push((file_path, target)),
+            Err(err) => {
+                let mut errors = self.errors.lock().await;
+
+                errors.push(ErrorData { file_path, error: err });
+            }
+        }
+        self.bar.lock().await.inc(1);
+    }
+}
+
+impl fmt::Display for ErrorData {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.error {
+            Cc2pError::ValueError {
+                line,
+                column,
+                value,
+                expected,
+                ..
+            } => write!(
+                f,
+                "File: {}  Line: {}  Column: {}  Value: {:?}  Expected: {}",
+                self.file_path, line, column, value, expected
+            ),
+            Cc2pError::RecordError { line, message, .. } => {
+                write!(f, "File: {}  Line: {}  Error: {}", self.file_path, line, message)
+            }
+            error => write!(f, "File: {}  Error: {}", self.file_path, error),
+        }
+    }
+}
+
+/// Prints a table with the statistics of every converted file.
+///
+/// The detected dialect is shown in a last column when `--auto` was used.
+fn print_stats(out: &mut dyn Write, stats: &[(String, ConversionStats)]) -> io::Result<()> {
+    let show_dialect = stats.iter().any(|(_, stats)| stats.dialect.is_some());
+    let mut headers = vec![
+        "File",
+        "Rows read",
+        "Rows written",
+        "Rejected",
+        "Row groups",
+        "Input",
+        "Output",
+        "Ratio",
+        "Time",
+    ];
+    if show_dialect {
+        headers.push("Dialect");
+    }
+
+    let rows: Vec<Vec<String>> = stats
+        .iter()
+        .map(|(file_path, stats)| {
+            let mut row = vec![
+                file_path.clone(),
+                stats.rows_read.to_string(),
+                stats.rows_written.to_string(),
+                stats.rows_rejected.to_string(),
+                stats.row_groups.to_string(),
+                format_byte_size(stats.input_bytes),
+                format_byte_size(stats.output_bytes),
+                format!("{:.1}x", stats.compression_ratio()),
+                format!("{} ms", stats.duration.as_millis()),
+            ];
+            if show_dialect {
+                row.push(stats.dialect.map(|d| d.to_string()).unwrap_or_default());
+            }
+            row
+        })
+        .collect();
+
+    let mut widths: Vec<usize> = headers.iter().map(|h| h.chars().count()).collect();
+    for row in &rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+
+    // The file name and the dialect are left aligned, the numbers are right aligned
+    let last = headers.len() - 1;
+    let format_row = |cells: Vec<&str>| {
+        cells
+            .iter()
+            .zip(&widths)
+            .enumerate()
+            .map(|(i, (cell, &width))| {
+                if i == 0 || (show_dialect && i == last) {
+                    format!("{:<width$}", cell)
+                } else {
+                    format!("{:>width$}", cell)
+                }
+            })
+            .collect::<Vec<_>>()
+            .join("  ")
+            .trim_end()
+            .to_string()
+    };
+
+    writeln!(out)?;
+    writeln!(out, "{}", format_row(headers.clone()))?;
+    for row in &rows {
+        writeln!(out, "{}", format_row(row.iter().map(String::as_str).collect()))?;
+    }
+    writeln!(out)
+}
+
+/// Returns the CSV files matching `path`, or stdin for `-`, and the archives that cannot be read.
+fn input_files(path: &str) -> cc2p::error::Result<Vec<FoundInput>> {
+    if path == STDIO {
+        Ok(vec![FoundInput::File(PathBuf::from(STDIO))])
+    } else {
+        find_inputs(path)
+    }
+}
+
+/// Builds the conversion options from the command line arguments.
+fn build_options(args: &Args) -> std::result::Result<ConvertOptions, Box<dyn std::error::Error>> {
+    let mut column_options = match &args.config {
+        Some(config_path) => load_config(config_path)?.columns,
+        None => Default::default(),
+    };
+    for (column, encoding) in &args.column_encoding {
+        column_options.entry(column.clone()).or_default().encoding = Some(*encoding);
+    }
+    for (column, dictionary) in &args.column_dictionary {
+        column_options.entry(column.clone()).or_default().dictionary = Some(*dictionary);
+    }
+    for (column, statistics) in &args.column_statistics {
+        column_options.entry(column.clone()).or_default().statistics = Some(*statistics);
+    }
+    for column in &args.bloom_filter {
+        let column_options = column_options.entry(column.clone()).or_default();
+        column_options.bloom_filter = Some(true);
+        column_options.bloom_filter_fpp = args.bloom_filter_fpp.or(column_options.bloom_filter_fpp);
+        column_options.bloom_filter_ndv = args.bloom_filter_ndv.or(column_options.bloom_filter_ndv);
+    }
+
+    let schema = match &args.schema {
+        Some(schema_path) => Some(Arc::new(load_schema(schema_path)?)),
+        None => None,
+    };
+
+    let options = ConvertOptions {
+        compression: args.compression,
+        compression_level: args.compression_level,
+        batch_size: args.batch_size,
+        max_row_group_rows: args.row_group_rows,
+        max_row_group_bytes: args.row_group_bytes,
+        data_page_size: args.data_page_size,
+        dictionary_page_size: args.dictionary_page_size,
+        write_batch_size: args.write_batch_size,
+        column_options,
+        schema,
+        schema_mode: args.schema_mode,
+        inference: args.inference,
+        widen_types: args.widen_types,
+        on_error: args.on_error,
+        max_bad_rows: args.max_bad_rows,
+        null_values: args.null_values.clone(),
+        true_values: args.true_values.clone(),
+        false_values: args.false_values.clone(),
+        trim: args.trim,
+        date_formats: args.date_formats.clone(),
+        timestamp_formats: args.timestamp_formats.clone(),
+        timestamp_unit: args.timestamp_unit,
+        timezone: args.timezone.clone(),
+        utc: args.utc,
+        decimal_separator: args.decimal_separator,
+        grouping_separator: args.grouping_separator,
+        strip_number_symbols: args.strip_number_symbols,
+        infer_decimals: args.infer_decimals,
+        quote: args.quote,
+        escape: args.escape,
+        double_quote: !args.no_double_quote,
+        comment: args.comment,
+        terminator: args.terminator,
+        auto_dialect: args.auto,
+        output: args.output.clone(),
+        output_dir: args.output_dir.clone(),
+        input_root: Some(glob_base(&args.path)),
+        output_template: args.output_template.clone(),
+        overwrite: args.overwrite,
+        encoding: args.encoding.clone(),
+        delimiter: parse_delimiter(&args.delimiter)?,
+        ..ConvertOptions::new(',', !args.no_header, args.sampling)
+    };
+    options.validate()?;
+
+    Ok(options)
 }
 
-fn main() -> std::result::Result<(), Box<dyn std::error::Error>> {
+/// Prints or writes the inferred schema of every CSV file matching `path`.
+///
+/// Values that widened a column type during inference are reported on stderr.
+fn print_schemas(
+    path: &str,
+    format: SchemaFormat,
+    write: bool,
+    options: &ConvertOptions,
+) -> std::result::Result<(), Box<dyn std::error::Error>> {
+    let mut files = Vec::new();
+    for input in input_files(path)? {
+        match input {
+            FoundInput::File(file) => files.push(file),
+            FoundInput::Unreadable(archive, e) => return Err(format!("{}: {}", archive.display(), e).into()),
+        }
+    }
+    if files.is_empty() {
+        return Err(format!("No CSV files found for path: {}", path).into());
+    }
+    if write && path == STDIO {
+        return Err("--write cannot be used when reading from stdin".into());
+    }
+
+    let extension = match format {
+        SchemaFormat::Json => "schema.json",
+        SchemaFormat::Toml => "schema.toml",
+    };
+
+    for (i, file) in files.iter().enumerate() {
+        let (schema, report) = infer_schema_with_report(file, options)?;
+        let content = schema_to_string(&remove_deduplicate_columns(schema), format)?;
+        for widening in &report.widenings {
+            eprintln!("{}: {}", file.display(), widening);
+        }
+
+        if write {
+            let target = output_path(file, extension);
+            std::fs::write(&target, content)?;
+            eprintln!("Schema of {} written to {}", file.display(), target.display());
+        } else if files.len() == 1 {
+            print!("{}", content);
+        } else {
+            if i > 0 {
+                println!();
+            }
+            println!("==> {} <==", file.display());
+            print!("{}", content);
+        }
+    }
+
+    Ok(())
+}
+
+fn main() -> ExitCode {
     let args = Args::parse();
+    let options = match build_options(&args) {
+        Ok(options) => options,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            return ExitCode::from(EXIT_USAGE_ERROR);
+        }
+    };
+
+    match run(&args, options) {
+        Ok(code) => ExitCode::from(code),
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            ExitCode::from(EXIT_TOTAL_FAILURE)
+        }
+    }
+}
+
+/// Runs the subcommand, the interactive UI or the conversion, and returns the exit code.
+fn run(args: &Args, options: ConvertOptions) -> std::result::Result<u8, Box<dyn std::error::Error>> {
     let start = Instant::now();
-    let path = args.path.as_str();
-    let sampling_size = args.sampling;
-    let has_header = !args.no_header;
-    let delimiter = args.delimiter.as_str().chars().next().unwrap_or(',');
 
-    println!(
-        "Program arguments\n path: {}\n delimiter: {}\n has header: {} \n worker count: {} \n sampling size {}",
-        path, delimiter, has_header, args.worker, sampling_size
-    );
+    if let Some(Command::Schema { path, format, write }) = &args.command {
+        print_schemas(path, *format, *write, &options)?;
+        return Ok(EXIT_SUCCESS);
+    }
+
+    // Keep stdout free for the Parquet file
+    let mut out: Box<dyn Write> = match &args.output {
+        Some(output) if is_stdio(output) => Box::new(io::stderr()),
+        _ => Box::new(io::stdout()),
+    };
+
+    let path = args.path.as_str();
+    writeln!(
+        out,
+        "Program arguments\n path: {}\n delimiter: {}\n has header: {} \n worker count: {} \n sampling size {} \n inference: {:?} \n compression: {}",
+        path,
+        options.delimiter.escape_debug(),
+        options.has_header,
+        args.worker,
+        options.sampling_size,
+        options.inference,
+        args.compression
+    )?;
 
     if args.interactive {
         let rt = runtime::Builder::new_multi_thread().enable_all().build()?;
-        rt.block_on(async {
-            if let Err(e) = run_tui(path, delimiter, has_header, sampling_size).await {
-                eprintln!("TUI Error: {}", e);
-            }
-        });
-        return Ok(());
+        rt.block_on(run_tui(path, options))?;
+        return Ok(EXIT_SUCCESS);
     }
 
     let errors = Arc::new(Mutex::new(Vec::<ErrorData>::new()));
+    let reports = Arc::new(Mutex::new(Vec::<FileReport>::new()));
+    let stats = Arc::new(Mutex::new(Vec::<(String, ConversionStats)>::new()));
+    let skipped = Arc::new(Mutex::new(Vec::<(String, PathBuf)>::new()));
 
-    let files = find_files(path)?;
+    let files = input_files(path)?;
+    if files.is_empty() {
+        eprintln!("Error: No CSV files found for path: {}", path);
+        return Ok(EXIT_USAGE_ERROR);
+    }
+    if args.output.is_some() && files.len() > 1 {
+        eprintln!(
+            "Error: --output needs a single input file, but {} files match {}",
+            files.len(),
+            path
+        );
+        return Ok(EXIT_USAGE_ERROR);
+    }
+    let file_count = files.len();
 
     let bar = ProgressBar::new(files.len().try_into()?);
 
     bar.set_style(ProgressStyle::with_template(
         "[{elapsed_precise}] {bar:40.yellow/blue} {pos:>7}/{len:7} {msg}",
     )?);
-    let bar = Arc::new(Mutex::new(bar));
+    let results = Results {
+        errors: Arc::clone(&errors),
+        reports: Arc::clone(&reports),
+        stats: Arc::clone(&stats),
+        skipped: Arc::clone(&skipped),
+        bar: Arc::new(Mutex::new(bar)),
+    };
 
     let runtime = runtime::Builder::new_multi_thread()
         .worker_threads(args.worker as usize)
@@ -134,19 +900,41 @@ fn main() -> std::result::Result<(), Box<dyn std::error::Error>> {
     runtime.block_on(async {
         let mut handles = vec![];
 
+        // Members of a tar archive are converted together, in a single pass over the archive
+        let mut tar_members: BTreeMap<PathBuf, Vec<PathBuf>> = BTreeMap::new();
         for file in files {
-            let bar = Arc::clone(&bar);
-            let errors_clone = Arc::clone(&errors);
+            // An archive that cannot be read counts as a failed input
+            let file = match file {
+                FoundInput::File(file) => file,
+                FoundInput::Unreadable(archive, e) => {
+                    results.record(&archive, Err(e), Duration::ZERO).await;
+                    continue;
+                }
+            };
+            if let Some(archive) = tar_archive(&file).map(Path::to_path_buf) {
+                tar_members.entry(archive).or_default().push(file);
+                continue;
+            }
+
+            let results = results.clone();
+            let options = options.clone();
             let h = tokio::spawn(async move {
-                if let Err(err) = convert_to_parquet(&file, delimiter, has_header, sampling_size).await {
-                    let mut errors = errors_clone.lock().await;
+                let file_start = Instant::now();
+                let result = convert_to_parquet_with_options(&file, &options).await;
+                results.record(&file, result, file_start.elapsed()).await;
+            });
+
+            handles.push(h);
+        }
 
-                    errors.push(ErrorData {
-                        file_path: file.to_str().unwrap_or("invalid path").to_string(),
-                        error: err.to_string(),
-                    });
+        for (archive, members) in tar_members {
+            let results = results.clone();
+            let options = options.clone();
+            let h = tokio::spawn(async move {
+                let converted = convert_tar_members(&archive, &members, &options).await;
+                for (member, (result, elapsed)) in members.iter().zip(converted) {
+                    results.record(member, result, elapsed).await;
                 }
-                bar.lock().await.inc(1);
             });
 
             handles.push(h);
@@ -161,17 +949,43 @@ fn main() -> std::result::Result<(), Box<dyn std::error::Error>> {
     let errors_guard = match errors.try_lock() {
         Ok(guard) => guard,
         Err(_) => {
-            println!("Warning: Could not acquire lock to display errors");
-            return Ok(());
+            writeln!(out, "Warning: Could not acquire lock to display errors")?;
+            return Ok(EXIT_TOTAL_FAILURE);
         }
     };
 
+    if let Ok(mut stats) = stats.try_lock()
+        && !stats.is_empty()
+    {
+        stats.sort_by(|a, b| a.0.cmp(&b.0));
+        print_stats(&mut out, &stats)?;
+    }
+
+    if let Ok(mut skipped) = skipped.try_lock() {
+        skipped.sort();
+        for (file_path, target) in &*skipped {
+            writeln!(out, "Skipped {}: {} already exists", file_path, target.display())?;
+        }
+    }
+
     for err_data in &*errors_guard {
-        println!("File: {}  Error: {:?}\n", err_data.file_path, err_data.error);
+        writeln!(out, "{}\n", err_data)?;
     }
 
     let elapsed = start.elapsed();
-    println!("Elapsed time {} ms", elapsed.as_millis());
+    writeln!(out, "Elapsed time {} ms", elapsed.as_millis())?;
 
-    Ok(())
+    if let Some(report_path) = &args.report {
+        let files = reports
+            .try_lock()
+            .map(|mut reports| std::mem::take(&mut *reports))
+            .unwrap_or_default();
+        RunReport::new(files, elapsed).write(report_path)?;
+    }
+
+    Ok(match errors_guard.len() {
+        0 => EXIT_SUCCESS,
+        failed if failed == file_count => EXIT_TOTAL_FAILURE,
+        _ => EXIT_PARTIAL_FAILURE,
+    })
 }
@@ -0,0 +1,108 @@
+use crate::options::ConvertOptions;
+
+/// Currency and percent symbols removed by `options.strip_number_symbols`.
+const NUMBER_SYMBOLS: &[char] = &['$', '€', '£', '¥', '₺', '₹', '₽', '₩', '₪', '₫', '฿', '₴', '₦', '¢', '%'];
+
+/// Rewrites a number written with the separators of `options` in the form arrow-csv parses, e.g. `1.234,56` with
+/// a `,` decimal separator and a `.` grouping separator becomes `1234.56`.
+///
+/// Grouping separators are only accepted between groups of three digits, so `1.5` is not read as `15`.
+///
+/// # Returns
+///
+/// Returns the rewritten number, or `None` if the value is not a number in this format or is already written
+/// in the standard form.
+pub(crate) fn normalize_number(value: &str, options: &ConvertOptions) -> Option<String> {
+    if options.decimal_separator == '.' && options.grouping_separator.is_none() && !options.strip_number_symbols {
+        return None;
+    }
+
+    let mut number = value.trim();
+    if options.strip_number_symbols {
+        number = number.trim_matches(|c: char| NUMBER_SYMBOLS.contains(&c) || c.is_whitespace());
+    }
+    let (negative, mut number) = match number.strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, number),
+    };
+    if options.strip_number_symbols {
+        number = number.trim_start_matches(|c: char| NUMBER_SYMBOLS.contains(&c) || c.is_whitespace());
+    }
+
+    let (integer, fraction) = match number.split_once(options.decimal_separator) {
+        Some((integer, fraction)) => (integer, Some(fraction)),
+        None => (number, None),
+    };
+    let integer = match options.grouping_separator {
+        Some(separator) => ungroup(integer, separator)?,
+        None => integer.to_string(),
+    };
+
+    let is_digits = |s: &str| !s.is_empty() && s.bytes().all(|b| b.is_ascii_digit());
+    if !is_digits(&integer) || fraction.is_some_and(|fraction| !is_digits(fraction)) {
+        return None;
+    }
+
+    let mut normalized = String::with_capacity(value.len());
+    if negative {
+        normalized.push('-');
+    }
+    normalized.push_str(&integer);
+    if let Some(fraction) = fraction {
+        normalized.push('.');
+        normalized.push_str(fraction);
+    }
+
+    (normalized != value).then_some(normalized)
+}
+
+/// Removes the grouping separators of an integer part, checking that every group after the first has three digits.
+///
+/// A space separator also accepts the no-break spaces many locales use.
+fn ungroup(integer: &str, separator: char) -> Option<String> {
+    let is_separator = |c: char| c == separator || (separator == ' ' && matches!(c, '\u{a0}' | '\u{202f}'));
+    let mut groups = integer.split(is_separator);
+    let first = groups.next()?;
+    if first.is_empty() || first.len() > 3 && integer.contains(is_separator) {
+        return None;
+    }
+
+    let mut digits = first.to_string();
+    for group in groups {
+        if group.len() != 3 {
+            return None;
+        }
+        digits.push_str(group);
+    }
+    Some(digits)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_number() {
+        let mut options = ConvertOptions::default();
+        assert_eq!(normalize_number("1.234,56", &options), None);
+
+        options.decimal_separator = ',';
+        options.grouping_separator = Some('.');
+        assert_eq!(normalize_number("1.234,56", &options).as_deref(), Some("1234.56"));
+        assert_eq!(normalize_number("-1.234.567", &options).as_deref(), Some("-1234567"));
+        assert_eq!(normalize_number("0,5", &options).as_deref(), Some("0.5"));
+        assert_eq!(normalize_number("42", &options), None);
+        assert_eq!(normalize_number("1.5", &options), None);
+        assert_eq!(normalize_number("1234.567", &options), None);
+        assert_eq!(normalize_number("€ 12,50", &options), None);
+
+        options.grouping_separator = Some(' ');
+        options.strip_number_symbols = true;
+        assert_eq!(normalize_number("1 234 567", &options).as_deref(), Some("1234567"));
+        assert_eq!(normalize_number("1\u{a0}234,50 €", &options).as_deref(), Some("1234.50"));
+        assert_eq!(normalize_number("-€12,50", &options).as_deref(), Some("-12.50"));
+        assert_eq!(normalize_number("12,5%", &options).as_deref(), Some("12.5"));
+        assert_eq!(normalize_number("abc", &options), None);
+        assert_eq!(normalize_number("%", &options), None);
+    }
+}
@@ -0,0 +1,1028 @@
+use crate::encoding::{AUTO_ENCODING, encoding_for_label};
+use crate::error::{Cc2pError, Result};
+use crate::inference::InferenceStrategy;
+use crate::utils::{is_stdio, render_template};
+use arrow_array::timezone::Tz;
+use arrow_schema::{DataType, SchemaRef, TimeUnit};
+use chrono::format::StrftimeItems;
+use parquet::basic::{BrotliLevel, Compression, Encoding, GzipLevel, ZstdLevel};
+use parquet::file::properties::EnabledStatistics;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::path::{Component, Path, PathBuf};
+use std::str::FromStr;
+
+/// Default number of CSV rows decoded into a single Arrow record batch.
+pub const DEFAULT_BATCH_SIZE: usize = 1024;
+
+/// Byte that replaces a delimiter which is not a single ASCII character while a file is read, the ASCII unit
+/// separator. Files read with such a delimiter cannot contain this byte.
+pub const DELIMITER_SUBSTITUTE: u8 = 0x1F;
+
+/// Placeholders of [`ConvertOptions::output_template`]: the name of the CSV file without its extensions, the name
+/// of its directory, the current date (`YYYY-MM-DD`) and the compression codec.
+pub const OUTPUT_TEMPLATE_FIELDS: [&str; 4] = ["stem", "parent", "date", "compression"];
+
+/// Compression codecs that can be used for the Parquet output.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum CompressionCodec {
+    /// No compression.
+    Uncompressed,
+    /// Snappy compression, a good balance between speed and ratio.
+    #[default]
+    Snappy,
+    /// Gzip compression, supports levels 0-9.
+    Gzip,
+    /// LZ4 raw block compression.
+    #[value(name = "lz4_raw")]
+    Lz4Raw,
+    /// Brotli compression, supports levels 0-11.
+    Brotli,
+    /// Zstandard compression, supports levels 1-22.
+    Zstd,
+}
+
+impl CompressionCodec {
+    /// Builds the Parquet compression setting for this codec.
+    ///
+    /// # Arguments
+    ///
+    /// * `level` - Optional compression level. Only gzip, brotli and zstd accept a level; when omitted the codec default is used.
+    ///
+    /// # Returns
+    ///
+    /// Returns the Parquet `Compression`, or a `ConfigError` if the level is not supported by the codec.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use cc2p::options::CompressionCodec;
+    ///
+    /// assert!(CompressionCodec::Zstd.to_compression(Some(9)).is_ok());
+    /// assert!(CompressionCodec::Snappy.to_compression(Some(9)).is_err());
+    /// ```
+    pub fn to_compression(self, level: Option<i32>) -> Result<Compression> {
+        let invalid_level = |e: parquet::errors::ParquetError| Cc2pError::ConfigError(format!("invalid {} compression level: {}", self, e));
+
+        let compression = match (self, level) {
+            (CompressionCodec::Uncompressed, None) => Compression::UNCOMPRESSED,
+            (CompressionCodec::Snappy, None) => Compression::SNAPPY,
+            (CompressionCodec::Lz4Raw, None) => Compression::LZ4_RAW,
+            (CompressionCodec::Gzip, None) => Compression::GZIP(GzipLevel::default()),
+            (CompressionCodec::Brotli, None) => Compression::BROTLI(BrotliLevel::default()),
+            (CompressionCodec::Zstd, None) => Compression::ZSTD(ZstdLevel::default()),
+            (CompressionCodec::Gzip, Some(level)) => {
+                let level = u32::try_from(level).map_err(|e| Cc2pError::ConfigError(format!("invalid gzip compression level: {}", e)))?;
+                Compression::GZIP(GzipLevel::try_new(level).map_err(invalid_level)?)
+            }
+            (CompressionCodec::Brotli, Some(level)) => {
+                let level = u32::try_from(level).map_err(|e| Cc2pError::ConfigError(format!("invalid brotli compression level: {}", e)))?;
+                Compression::BROTLI(BrotliLevel::try_new(level).map_err(invalid_level)?)
+            }
+            (CompressionCodec::Zstd, Some(level)) => Compression::ZSTD(ZstdLevel::try_new(level).map_err(invalid_level)?),
+            (codec, Some(_)) => {
+                return Err(Cc2pError::ConfigError(format!(
+                    "{} compression does not support a compression level",
+                    codec
+                )));
+            }
+        };
+
+        Ok(compression)
+    }
+}
+
+impl fmt::Display for CompressionCodec {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            CompressionCodec::Uncompressed => "uncompressed",
+            CompressionCodec::Snappy => "snappy",
+            CompressionCodec::Gzip => "gzip",
+            CompressionCodec::Lz4Raw => "lz4_raw",
+            CompressionCodec::Brotli => "brotli",
+            CompressionCodec::Zstd => "zstd",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// How a schema file is combined with the inferred schema.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum SchemaMode {
+    /// Replaces the type and nullability of the inferred columns listed in the schema file.
+    #[default]
+    Override,
+    /// Uses the schema file as the complete schema and skips inference.
+    Replace,
+}
+
+/// What happens when a row cannot be read or converted.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum OnError {
+    /// Fails the conversion of the file.
+    #[default]
+    Fail,
+    /// Drops the row and continues.
+    Skip,
+    /// Drops the row and writes it to a `<name>.rejects.csv` file next to the output.
+    Quarantine,
+}
+
+/// What happens when the Parquet file of a CSV file already exists.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum OverwritePolicy {
+    /// Replaces the existing file.
+    #[default]
+    Always,
+    /// Keeps the existing file and skips the CSV file.
+    Never,
+    /// Replaces the existing file only if the CSV file was modified after it.
+    IfNewer,
+}
+
+/// Units of the timestamp columns written to the Parquet file.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, clap::ValueEnum)]
+pub enum TimestampUnit {
+    /// Seconds.
+    #[value(name = "s")]
+    Second,
+    /// Milliseconds.
+    #[value(name = "ms")]
+    Millisecond,
+    /// Microseconds.
+    #[value(name = "us")]
+    Microsecond,
+    /// Nanoseconds.
+    #[value(name = "ns")]
+    Nanosecond,
+}
+
+impl TimestampUnit {
+    /// Returns the Arrow time unit for this value.
+    pub fn to_time_unit(self) -> TimeUnit {
+        match self {
+            TimestampUnit::Second => TimeUnit::Second,
+            TimestampUnit::Millisecond => TimeUnit::Millisecond,
+            TimestampUnit::Microsecond => TimeUnit::Microsecond,
+            TimestampUnit::Nanosecond => TimeUnit::Nanosecond,
+        }
+    }
+}
+
+/// Value encodings that can be requested for a single column.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize)]
+#[serde(try_from = "String")]
+pub enum ColumnEncoding {
+    /// Plain encoding, supported by all types.
+    Plain,
+    /// Delta encoding for integer columns.
+    DeltaBinaryPacked,
+    /// Delta encoding of string lengths followed by the concatenated strings.
+    DeltaLengthByteArray,
+    /// Incremental (prefix) encoding for string columns.
+    DeltaByteArray,
+    /// Byte stream split encoding, mostly useful for floating point columns.
+    ByteStreamSplit,
+    /// Run length encoding, only supported by boolean columns.
+    Rle,
+}
+
+impl ColumnEncoding {
+    /// Returns the Parquet encoding for this value.
+    pub fn to_encoding(self) -> Encoding {
+        match self {
+            ColumnEncoding::Plain => Encoding::PLAIN,
+            ColumnEncoding::DeltaBinaryPacked => Encoding::DELTA_BINARY_PACKED,
+            ColumnEncoding::DeltaLengthByteArray => Encoding::DELTA_LENGTH_BYTE_ARRAY,
+            ColumnEncoding::DeltaByteArray => Encoding::DELTA_BYTE_ARRAY,
+            ColumnEncoding::ByteStreamSplit => Encoding::BYTE_STREAM_SPLIT,
+            ColumnEncoding::Rle => Encoding::RLE,
+        }
+    }
+}
+
+impl FromStr for ColumnEncoding {
+    type Err = Cc2pError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_ascii_uppercase().replace('-', "_").as_str() {
+            "PLAIN" => Ok(ColumnEncoding::Plain),
+            "DELTA_BINARY_PACKED" => Ok(ColumnEncoding::DeltaBinaryPacked),
+            "DELTA_LENGTH_BYTE_ARRAY" => Ok(ColumnEncoding::DeltaLengthByteArray),
+            "DELTA_BYTE_ARRAY" => Ok(ColumnEncoding::DeltaByteArray),
+            "BYTE_STREAM_SPLIT" => Ok(ColumnEncoding::ByteStreamSplit),
+            "RLE" => Ok(ColumnEncoding::Rle),
+            _ => Err(Cc2pError::ConfigError(format!("unknown column encoding '{}'", s))),
+        }
+    }
+}
+
+impl TryFrom<String> for ColumnEncoding {
+    type Error = Cc2pError;
+
+    fn try_from(value: String) -> Result<Self> {
+        value.parse()
+    }
+}
+
+/// Level of statistics written for a column.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize)]
+#[serde(try_from = "String")]
+pub enum StatisticsLevel {
+    /// No statistics.
+    None,
+    /// Statistics per column chunk.
+    Chunk,
+    /// Statistics per column chunk and per page, including the column index.
+    Page,
+}
+
+impl StatisticsLevel {
+    /// Returns the Parquet statistics setting for this level.
+    pub fn to_enabled_statistics(self) -> EnabledStatistics {
+        match self {
+            StatisticsLevel::None => EnabledStatistics::None,
+            StatisticsLevel::Chunk => EnabledStatistics::Chunk,
+            StatisticsLevel::Page => EnabledStatistics::Page,
+        }
+    }
+}
+
+impl FromStr for StatisticsLevel {
+    type Err = Cc2pError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "none" => Ok(StatisticsLevel::None),
+            "chunk" => Ok(StatisticsLevel::Chunk),
+            "page" => Ok(StatisticsLevel::Page),
+            _ => Err(Cc2pError::ConfigError(format!("unknown statistics level '{}'", s))),
+        }
+    }
+}
+
+impl TryFrom<String> for StatisticsLevel {
+    type Error = Cc2pError;
+
+    fn try_from(value: String) -> Result<Self> {
+        value.parse()
+    }
+}
+
+/// Writer overrides for a single column. Unset fields keep the writer defaults.
+#[derive(Clone, Debug, Default, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ColumnOptions {
+    /// Value encoding. While the dictionary is enabled it is only used as the fallback encoding.
+    pub encoding: Option<ColumnEncoding>,
+
+    /// Enables or disables dictionary encoding.
+    pub dictionary: Option<bool>,
+
+    /// Level of statistics written for the column.
+    pub statistics: Option<StatisticsLevel>,
+
+    /// Writes a bloom filter for the column.
+    pub bloom_filter: Option<bool>,
+
+    /// Target false positive probability of the bloom filter, between 0 and 1 exclusive.
+    pub bloom_filter_fpp: Option<f64>,
+
+    /// Expected number of distinct values per row group, used to size the bloom filter.
+    pub bloom_filter_ndv: Option<u64>,
+}
+
+impl ColumnOptions {
+    /// Returns `true` if a bloom filter is written for the column.
+    pub fn has_bloom_filter(&self) -> bool {
+        self.bloom_filter == Some(true)
+    }
+}
+
+/// Options controlling how a CSV file is read and how the Parquet file is written.
+///
+/// # Examples
+///
+/// ```rust
+/// use cc2p::options::{CompressionCodec, ConvertOptions};
+///
+/// let options = ConvertOptions {
+///     compression: CompressionCodec::Zstd,
+///     compression_level: Some(9),
+///     ..ConvertOptions::new(',', true, 2048)
+/// };
+/// assert_eq!(options.delimiter, ",");
+/// ```
+#[derive(Clone, Debug)]
+pub struct ConvertOptions {
+    /// The delimiter used in the CSV file: a single character such as `,` or `§`, or a sequence such as `||`.
+    pub delimiter: String,
+
+    /// Indicates whether the CSV file has a header row.
+    pub has_header: bool,
+
+    /// The quote character used in the CSV file.
+    pub quote: char,
+
+    /// Escape character for quotes inside quoted fields, e.g. `\\` for `\"`. `None` only accepts doubled quotes.
+    pub escape: Option<char>,
+
+    /// Reads two adjacent quotes inside a quoted field as a single quote.
+    pub double_quote: bool,
+
+    /// Lines starting with this character are skipped.
+    pub comment: Option<char>,
+
+    /// Record terminator. `None` accepts `\n`, `\r` and `\r\n`.
+    pub terminator: Option<char>,
+
+    /// Detects the delimiter, quote character and header of every file with
+    /// [`sniff_dialect`](crate::dialect::sniff_dialect), replacing the settings above.
+    pub auto_dialect: bool,
+
+    /// Text encoding of the CSV file, e.g. `windows-1252`, `latin1` or `utf-16le`, or `auto` to guess it from the
+    /// first bytes. `None` reads UTF-8. A byte order mark takes precedence and is removed.
+    pub encoding: Option<String>,
+
+    /// The number of rows to sample for inferring the schema. Ignored by [`InferenceStrategy::Full`].
+    pub sampling_size: usize,
+
+    /// How the rows used for inferring the schema are selected.
+    pub inference: InferenceStrategy,
+
+    /// The names of the columns to be included in the Parquet file. `None` exports all columns.
+    pub selected_columns: Option<Vec<String>>,
+
+    /// Path of the Parquet file, or `-` to write it to stdout. `None` writes it next to the CSV file, see
+    /// [`output_path`](crate::utils::output_path).
+    pub output: Option<PathBuf>,
+
+    /// Directory the Parquet files are written to, mirroring the directories of the CSV files below `input_root`.
+    /// `None` writes them next to the CSV files.
+    pub output_dir: Option<PathBuf>,
+
+    /// Directory whose structure is mirrored into `output_dir`, usually the directory a glob pattern searches in,
+    /// see [`glob_base`](crate::utils::glob_base). `None` writes every file directly into `output_dir`.
+    pub input_root: Option<PathBuf>,
+
+    /// Name of the Parquet files, e.g. `{stem}_{date}.parquet`, see [`OUTPUT_TEMPLATE_FIELDS`]. The name may
+    /// contain directories. `None` uses `{stem}.parquet`.
+    pub output_template: Option<String>,
+
+    /// What happens when the Parquet file already exists. Files are replaced atomically, so a failed conversion
+    /// keeps the previous file.
+    pub overwrite: OverwritePolicy,
+
+    /// The compression codec used for the Parquet file.
+    pub compression: CompressionCodec,
+
+    /// Optional compression level for codecs that support one.
+    pub compression_level: Option<i32>,
+
+    /// Number of CSV rows decoded into a single record batch. Defaults to [`DEFAULT_BATCH_SIZE`].
+    pub batch_size: Option<usize>,
+
+    /// Maximum number of rows in a row group.
+    pub max_row_group_rows: Option<usize>,
+
+    /// Maximum estimated size of a row group in bytes.
+    pub max_row_group_bytes: Option<usize>,
+
+    /// Best-effort maximum size of a data page in bytes.
+    pub data_page_size: Option<usize>,
+
+    /// Best-effort maximum size of a dictionary page in bytes.
+    pub dictionary_page_size: Option<usize>,
+
+    /// Number of values the Parquet writer processes per column write call.
+    pub write_batch_size: Option<usize>,
+
+    /// Per-column writer overrides, keyed by the column name written to the Parquet file.
+    pub column_options: BTreeMap<String, ColumnOptions>,
+
+    /// Explicit schema, usually loaded with [`load_schema`](crate::schema::load_schema).
+    pub schema: Option<SchemaRef>,
+
+    /// How `schema` is combined with the inferred schema.
+    pub schema_mode: SchemaMode,
+
+    /// Widens a column and retries the conversion when a value does not match its type, instead of failing.
+    pub widen_types: bool,
+
+    /// What happens when a row cannot be read or converted.
+    pub on_error: OnError,
+
+    /// Maximum number of rows dropped by [`OnError::Skip`] or [`OnError::Quarantine`] before the conversion fails.
+    /// `None` allows any number of bad rows.
+    pub max_bad_rows: Option<usize>,
+
+    /// Values read as null, in addition to the empty value.
+    pub null_values: Vec<String>,
+
+    /// Values read as `true` in boolean columns, in addition to `true`. Compared case-insensitively.
+    pub true_values: Vec<String>,
+
+    /// Values read as `false` in boolean columns, in addition to `false`. Compared case-insensitively.
+    pub false_values: Vec<String>,
+
+    /// Removes leading and trailing whitespace from the headers and values.
+    pub trim: bool,
+
+    /// `chrono` format strings of dates, e.g. `%d/%m/%Y`, tried in order before the ISO 8601 format.
+    pub date_formats: Vec<String>,
+
+    /// `chrono` format strings of timestamps, e.g. `%Y-%m-%d %H:%M:%S %z`, tried in order before the ISO 8601 format.
+    pub timestamp_formats: Vec<String>,
+
+    /// Unit of the inferred timestamp columns. `None` picks the unit from the fractional seconds of the values.
+    pub timestamp_unit: Option<TimestampUnit>,
+
+    /// Timezone of timestamps without an offset, e.g. `Europe/Istanbul` or `+03:00`. Inferred timestamp columns
+    /// are written with this timezone.
+    pub timezone: Option<String>,
+
+    /// Writes the inferred timestamp columns in UTC.
+    pub utc: bool,
+
+    /// Decimal separator of numbers, e.g. `,` for `1234,56`.
+    pub decimal_separator: char,
+
+    /// Grouping (thousands) separator of numbers, e.g. `.` for `1.234,56` or ` ` for `1 234 567`.
+    pub grouping_separator: Option<char>,
+
+    /// Removes currency and percent symbols around numbers, e.g. `€ 12,50` or `12%`.
+    pub strip_number_symbols: bool,
+
+    /// Infers `Decimal128` instead of Float64 for columns of fixed-point numbers, with the precision and scale of
+    /// the sampled values.
+    pub infer_decimals: bool,
+}
+
+/// The single-byte dialect characters of [`ConvertOptions`], see [`ConvertOptions::dialect_bytes`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) struct DialectBytes {
+    pub(crate) delimiter: u8,
+    pub(crate) quote: u8,
+    pub(crate) escape: Option<u8>,
+    pub(crate) comment: Option<u8>,
+    pub(crate) terminator: Option<u8>,
+}
+
+impl ConvertOptions {
+    /// Creates options with the given CSV settings and default writer settings.
+    ///
+    /// # Arguments
+    ///
+    /// * `delimiter` - The delimiter character used in the CSV file.
+    /// * `has_header` - Indicates whether the CSV file has a header row.
+    /// * `sampling_size` - The number of rows to sample for inferring the schema.
+    pub fn new(delimiter: char, has_header: bool, sampling_size: usize) -> Self {
+        ConvertOptions {
+            delimiter: delimiter.to_string(),
+            has_header,
+            sampling_size,
+            ..ConvertOptions::default()
+        }
+    }
+
+    /// Returns the delimiter byte of the CSV reader.
+    ///
+    /// Delimiters that are not a single ASCII character are replaced by [`DELIMITER_SUBSTITUTE`] while the file
+    /// is read, see [`ConvertOptions::delimiter_sequence`].
+    pub fn delimiter_byte(&self) -> u8 {
+        match self.delimiter.as_bytes() {
+            [byte] => *byte,
+            _ => DELIMITER_SUBSTITUTE,
+        }
+    }
+
+    /// Returns the bytes of the delimiter if it is not a single ASCII character, e.g. `§` or `||`.
+    pub fn delimiter_sequence(&self) -> Option<&[u8]> {
+        match self.delimiter.as_bytes() {
+            [_] => None,
+            bytes => Some(bytes),
+        }
+    }
+
+    /// Returns the dialect characters as the bytes the CSV readers work with.
+    ///
+    /// # Returns
+    ///
+    /// Returns a `ConfigError` if the quote, escape, comment or terminator character is not a single ASCII character.
+    pub(crate) fn dialect_bytes(&self) -> Result<DialectBytes> {
+        let byte = |name: &str, c: char| {
+            u8::try_from(c)
+                .ok()
+                .filter(u8::is_ascii)
+                .ok_or_else(|| Cc2pError::ConfigError(format!("the {} character '{}' must be a single ASCII character", name, c)))
+        };
+        Ok(DialectBytes {
+            delimiter: self.delimiter_byte(),
+            quote: byte("quote", self.quote)?,
+            escape: self.escape.map(|c| byte("escape", c)).transpose()?,
+            comment: self.comment.map(|c| byte("comment", c)).transpose()?,
+            terminator: self.terminator.map(|c| byte("terminator", c)).transpose()?,
+        })
+    }
+
+    /// Returns `true` if the value is one of the configured null values.
+    pub fn is_null_value(&self, value: &str) -> bool {
+        self.null_values.iter().any(|null| null == value)
+    }
+
+    /// Returns the boolean a value stands for if it is one of the configured true or false values.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use cc2p::options::ConvertOptions;
+    ///
+    /// let options = ConvertOptions {
+    ///     true_values: vec!["Y".to_string()],
+    ///     false_values: vec!["N".to_string()],
+    ///     ..ConvertOptions::default()
+    /// };
+    /// assert_eq!(options.boolean_value("y"), Some(true));
+    /// assert_eq!(options.boolean_value("maybe"), None);
+    /// ```
+    pub fn boolean_value(&self, value: &str) -> Option<bool> {
+        if self.true_values.iter().any(|token| token.eq_ignore_ascii_case(value)) {
+            Some(true)
+        } else if self.false_values.iter().any(|token| token.eq_ignore_ascii_case(value)) {
+            Some(false)
+        } else {
+            None
+        }
+    }
+
+    /// Returns the type of an inferred timestamp column, applying the timestamp unit and timezone settings.
+    ///
+    /// # Arguments
+    ///
+    /// * `unit` - The unit picked from the values of the column.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use arrow_schema::{DataType, TimeUnit};
+    /// use cc2p::options::{ConvertOptions, TimestampUnit};
+    ///
+    /// let options = ConvertOptions {
+    ///     timestamp_unit: Some(TimestampUnit::Millisecond),
+    ///     utc: true,
+    ///     ..ConvertOptions::default()
+    /// };
+    /// assert_eq!(
+    ///     options.timestamp_type(TimeUnit::Second),
+    ///     DataType::Timestamp(TimeUnit::Millisecond, Some("UTC".into()))
+    /// );
+    /// ```
+    pub fn timestamp_type(&self, unit: TimeUnit) -> DataType {
+        let unit = self.timestamp_unit.map_or(unit, TimestampUnit::to_time_unit);
+        let timezone = if self.utc { Some("UTC".to_string()) } else { self.timezone.clone() };
+        DataType::Timestamp(unit, timezone.map(Into::into))
+    }
+
+    /// Returns the Parquet compression setting for these options.
+    pub fn compression(&self) -> Result<Compression> {
+        self.compression.to_compression(self.compression_level)
+    }
+
+    /// Checks that the options can be used for a conversion.
+    ///
+    /// # Returns
+    ///
+    /// Returns `Ok(())` if the options are valid, otherwise returns a `ConfigError`.
+    pub fn validate(&self) -> Result<()> {
+        self.compression()?;
+
+        let sizes = [
+            ("batch size", self.batch_size),
+            ("max row group rows", self.max_row_group_rows),
+            ("max row group bytes", self.max_row_group_bytes),
+            ("data page size", self.data_page_size),
+            ("dictionary page size", self.dictionary_page_size),
+            ("write batch size", self.write_batch_size),
+        ];
+        for (name, value) in sizes {
+            if value == Some(0) {
+                return Err(Cc2pError::ConfigError(format!("{} must be greater than zero", name)));
+            }
+        }
+
+        if let Some(token) = self
+            .true_values
+            .iter()
+            .find(|token| self.false_values.iter().any(|other| other.eq_ignore_ascii_case(token)))
+        {
+            return Err(Cc2pError::ConfigError(format!(
+                "'{}' cannot be both a true and a false value",
+                token
+            )));
+        }
+
+        self.dialect_bytes()?;
+
+        if self.delimiter.is_empty() {
+            return Err(Cc2pError::ConfigError("the delimiter cannot be empty".to_string()));
+        }
+        let reserved = [Some(self.quote), Some('\n'), Some('\r'), self.terminator, self.escape];
+        if let Some(c) = self.delimiter.chars().find(|c| reserved.contains(&Some(*c))) {
+            return Err(Cc2pError::ConfigError(format!(
+                "the delimiter '{}' cannot contain the quote, escape or line terminator character {:?}",
+                self.delimiter, c
+            )));
+        }
+        if self.delimiter_sequence().is_some() && self.delimiter.contains(char::from(DELIMITER_SUBSTITUTE)) {
+            return Err(Cc2pError::ConfigError(format!(
+                "the delimiter '{}' cannot contain the byte 0x1F",
+                self.delimiter.escape_debug()
+            )));
+        }
+
+        if self.grouping_separator == Some(self.decimal_separator) {
+            return Err(Cc2pError::ConfigError(
+                "the decimal and grouping separators must be different".to_string(),
+            ));
+        }
+        if self.decimal_separator.is_ascii_digit() || self.grouping_separator.is_some_and(|c| c.is_ascii_digit() || c == '-') {
+            return Err(Cc2pError::ConfigError("number separators cannot be digits or '-'".to_string()));
+        }
+
+        for format in self.date_formats.iter().chain(&self.timestamp_formats) {
+            if StrftimeItems::new(format).parse().is_err() {
+                return Err(Cc2pError::ConfigError(format!("invalid date or timestamp format '{}'", format)));
+            }
+        }
+
+        if self.on_error == OnError::Quarantine && self.output.as_deref().is_some_and(is_stdio) {
+            return Err(Cc2pError::ConfigError(
+                "quarantined rows cannot be written when the Parquet file is written to stdout".to_string(),
+            ));
+        }
+
+        if let Some(template) = &self.output_template {
+            let name = render_template(template, &OUTPUT_TEMPLATE_FIELDS.map(|field| (field, "x")))?;
+            if name.is_empty() || name.ends_with('/') {
+                return Err(Cc2pError::ConfigError(format!(
+                    "the output template '{}' has no file name",
+                    template
+                )));
+            }
+            // The Parquet files stay below the directory they are written to
+            let path = Path::new(&name);
+            if path.has_root() || path.components().any(|c| c == Component::ParentDir) {
+                return Err(Cc2pError::ConfigError(format!(
+                    "the output template '{}' must be a relative path without '..'",
+                    template
+                )));
+            }
+        }
+
+        if let Some(encoding) = &self.encoding
+            && !encoding.eq_ignore_ascii_case(AUTO_ENCODING)
+            && encoding_for_label(encoding).is_none()
+        {
+            return Err(Cc2pError::ConfigError(format!("unknown encoding '{}'", encoding)));
+        }
+
+        if let Some(timezone) = &self.timezone {
+            timezone
+                .parse::<Tz>()
+                .map_err(|e| Cc2pError::ConfigError(format!("invalid timezone '{}': {}", timezone, e)))?;
+        }
+
+        for (name, column) in &self.column_options {
+            if let Some(fpp) = column.bloom_filter_fpp
+                && !(fpp > 0.0 && fpp < 1.0)
+            {
+                return Err(Cc2pError::ConfigError(format!(
+                    "bloom filter fpp of column '{}' must be between 0 and 1 exclusive",
+                    name
+                )));
+            }
+            if column.bloom_filter_ndv == Some(0) {
+                return Err(Cc2pError::ConfigError(format!(
+                    "bloom filter ndv of column '{}' must be greater than zero",
+                    name
+                )));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl Default for ConvertOptions {
+    fn default() -> Self {
+        ConvertOptions {
+            delimiter: ",".to_string(),
+            has_header: true,
+            quote: '"',
+            escape: None,
+            double_quote: true,
+            comment: None,
+            terminator: None,
+            auto_dialect: false,
+            encoding: None,
+            sampling_size: 2048,
+            inference: InferenceStrategy::default(),
+            selected_columns: None,
+            output: None,
+            output_dir: None,
+            input_root: None,
+            output_template: None,
+            overwrite: OverwritePolicy::default(),
+            compression: CompressionCodec::default(),
+            compression_level: None,
+            batch_size: None,
+            max_row_group_rows: None,
+            max_row_group_bytes: None,
+            data_page_size: None,
+            dictionary_page_size: None,
+            write_batch_size: None,
+            column_options: BTreeMap::new(),
+            schema: None,
+            schema_mode: SchemaMode::default(),
+            widen_types: false,
+            on_error: OnError::default(),
+            max_bad_rows: None,
+            null_values: Vec::new(),
+            true_values: Vec::new(),
+            false_values: Vec::new(),
+            trim: false,
+            date_formats: Vec::new(),
+            timestamp_formats: Vec::new(),
+            timestamp_unit: None,
+            timezone: None,
+            utc: false,
+            decimal_separator: '.',
+            grouping_separator: None,
+            strip_number_symbols: false,
+            infer_decimals: false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compression_without_level() {
+        assert_eq!(
+            CompressionCodec::Uncompressed.to_compression(None).unwrap(),
+            Compression::UNCOMPRESSED
+        );
+        assert_eq!(CompressionCodec::Snappy.to_compression(None).unwrap(), Compression::SNAPPY);
+        assert_eq!(CompressionCodec::Lz4Raw.to_compression(None).unwrap(), Compression::LZ4_RAW);
+        assert_eq!(
+            CompressionCodec::Zstd.to_compression(None).unwrap(),
+            Compression::ZSTD(ZstdLevel::default())
+        );
+    }
+
+    #[test]
+    fn test_compression_with_level() {
+        assert_eq!(
+            CompressionCodec::Zstd.to_compression(Some(9)).unwrap(),
+            Compression::ZSTD(ZstdLevel::try_new(9).unwrap())
+        );
+        assert_eq!(
+            CompressionCodec::Gzip.to_compression(Some(6)).unwrap(),
+            Compression::GZIP(GzipLevel::try_new(6).unwrap())
+        );
+        assert_eq!(
+            CompressionCodec::Brotli.to_compression(Some(11)).unwrap(),
+            Compression::BROTLI(BrotliLevel::try_new(11).unwrap())
+        );
+    }
+
+    #[test]
+    fn test_compression_invalid_level() {
+        assert!(matches!(
+            CompressionCodec::Snappy.to_compression(Some(1)),
+            Err(Cc2pError::ConfigError(_))
+        ));
+        assert!(matches!(
+            CompressionCodec::Lz4Raw.to_compression(Some(1)),
+            Err(Cc2pError::ConfigError(_))
+        ));
+        assert!(matches!(
+            CompressionCodec::Gzip.to_compression(Some(42)),
+            Err(Cc2pError::ConfigError(_))
+        ));
+        assert!(matches!(
+            CompressionCodec::Gzip.to_compression(Some(-1)),
+            Err(Cc2pError::ConfigError(_))
+        ));
+        assert!(matches!(
+            CompressionCodec::Zstd.to_compression(Some(99)),
+            Err(Cc2pError::ConfigError(_))
+        ));
+    }
+
+    #[test]
+    fn test_parse_column_encoding() {
+        assert_eq!(
+            "DELTA_BINARY_PACKED".parse::<ColumnEncoding>().unwrap(),
+            ColumnEncoding::DeltaBinaryPacked
+        );
+        assert_eq!(
+            "byte_stream_split".parse::<ColumnEncoding>().unwrap(),
+            ColumnEncoding::ByteStreamSplit
+        );
+        assert_eq!(
+            "delta-length-byte-array".parse::<ColumnEncoding>().unwrap(),
+            ColumnEncoding::DeltaLengthByteArray
+        );
+        assert!(matches!("RLE_DICTIONARY".parse::<ColumnEncoding>(), Err(Cc2pError::ConfigError(_))));
+    }
+
+    #[test]
+    fn test_parse_statistics_level() {
+        assert_eq!("none".parse::<StatisticsLevel>().unwrap(), StatisticsLevel::None);
+        assert_eq!("CHUNK".parse::<StatisticsLevel>().unwrap(), StatisticsLevel::Chunk);
+        assert_eq!("Page".parse::<StatisticsLevel>().unwrap(), StatisticsLevel::Page);
+        assert!("row".parse::<StatisticsLevel>().is_err());
+    }
+
+    #[test]
+    fn test_validate_sizes() {
+        assert!(ConvertOptions::default().validate().is_ok());
+
+        let options = ConvertOptions {
+            max_row_group_rows: Some(1000),
+            data_page_size: Some(64 * 1024),
+            ..ConvertOptions::default()
+        };
+        assert!(options.validate().is_ok());
+
+        let options = ConvertOptions {
+            batch_size: Some(0),
+            ..ConvertOptions::default()
+        };
+        assert!(matches!(options.validate(), Err(Cc2pError::ConfigError(_))));
+    }
+
+    #[test]
+    fn test_validate_bloom_filter() {
+        let mut options = ConvertOptions::default();
+        options.column_options.insert(
+            "id".to_string(),
+            ColumnOptions {
+                bloom_filter: Some(true),
+                bloom_filter_fpp: Some(0.01),
+                bloom_filter_ndv: Some(100_000),
+                ..ColumnOptions::default()
+            },
+        );
+        assert!(options.validate().is_ok());
+        assert!(options.column_options["id"].has_bloom_filter());
+
+        options.column_options.get_mut("id").unwrap().bloom_filter_fpp = Some(1.0);
+        assert!(matches!(options.validate(), Err(Cc2pError::ConfigError(_))));
+
+        options.column_options.get_mut("id").unwrap().bloom_filter_fpp = None;
+        options.column_options.get_mut("id").unwrap().bloom_filter_ndv = Some(0);
+        assert!(matches!(options.validate(), Err(Cc2pError::ConfigError(_))));
+    }
+
+    #[test]
+    fn test_validate_boolean_values() {
+        let mut options = ConvertOptions {
+            true_values: vec!["Y".to_string(), "yes".to_string()],
+            false_values: vec!["N".to_string()],
+            ..ConvertOptions::default()
+        };
+        assert!(options.validate().is_ok());
+
+        options.false_values.push("YES".to_string());
+        assert!(matches!(options.validate(), Err(Cc2pError::ConfigError(_))));
+    }
+
+    #[test]
+    fn test_validate_date_formats_and_timezone() {
+        let mut options = ConvertOptions {
+            date_formats: vec!["%d/%m/%Y".to_string()],
+            timestamp_formats: vec!["%Y-%m-%d %H:%M:%S %z".to_string()],
+            timezone: Some("Europe/Istanbul".to_string()),
+            ..ConvertOptions::default()
+        };
+        assert!(options.validate().is_ok());
+        assert_eq!(
+            options.timestamp_type(TimeUnit::Second),
+            DataType::Timestamp(TimeUnit::Second, Some("Europe/Istanbul".into()))
+        );
+
+        options.timezone = Some("Mars/Olympus".to_string());
+        assert!(matches!(options.validate(), Err(Cc2pError::ConfigError(_))));
+
+        options.timezone = Some("+03:00".to_string());
+        options.date_formats.push("%Q".to_string());
+        assert!(matches!(options.validate(), Err(Cc2pError::ConfigError(_))));
+    }
+
+    #[test]
+    fn test_validate_number_separators() {
+        let mut options = ConvertOptions {
+            decimal_separator: ',',
+            grouping_separator: Some('.'),
+            ..ConvertOptions::default()
+        };
+        assert!(options.validate().is_ok());
+
+        options.grouping_separator = Some(',');
+        assert!(matches!(options.validate(), Err(Cc2pError::ConfigError(_))));
+
+        options.grouping_separator = Some('1');
+        assert!(matches!(options.validate(), Err(Cc2pError::ConfigError(_))));
+    }
+
+    #[test]
+    fn test_validate_dialect() {
+        let mut options = ConvertOptions {
+            quote: '\'',
+            escape: Some('\\'),
+            comment: Some('#'),
+            terminator: Some(';'),
+            ..ConvertOptions::default()
+        };
+        assert!(options.validate().is_ok());
+
+        options.comment = Some('§');
+        assert!(matches!(options.validate(), Err(Cc2pError::ConfigError(_))));
+
+        options.comment = None;
+        options.terminator = Some(',');
+        assert!(matches!(options.validate(), Err(Cc2pError::ConfigError(_))));
+    }
+
+    #[test]
+    fn test_validate_encoding() {
+        let mut options = ConvertOptions::default();
+        for encoding in ["windows-1252", "Latin1", "UTF-16LE", "auto"] {
+            options.encoding = Some(encoding.to_string());
+            assert!(options.validate().is_ok(), "{}", encoding);
+        }
+
+        options.encoding = Some("klingon".to_string());
+        assert!(matches!(options.validate(), Err(Cc2pError::ConfigError(_))));
+    }
+
+    #[test]
+    fn test_validate_output_template() {
+        let mut options = ConvertOptions {
+            output_template: Some("{parent}/{stem}_{date}.{compression}.parquet".to_string()),
+            ..ConvertOptions::default()
+        };
+        assert!(options.validate().is_ok());
+
+        options.output_template = Some("{stem}_{hour}.parquet".to_string());
+        assert!(matches!(options.validate(), Err(Cc2pError::ConfigError(_))));
+
+        options.output_template = Some("{parent}/".to_string());
+        assert!(matches!(options.validate(), Err(Cc2pError::ConfigError(_))));
+
+        options.output_template = Some("/tmp/{stem}.parquet".to_string());
+        assert!(matches!(options.validate(), Err(Cc2pError::ConfigError(_))));
+
+        options.output_template = Some("../{stem}.parquet".to_string());
+        assert!(matches!(options.validate(), Err(Cc2pError::ConfigError(_))));
+    }
+
+    #[test]
+    fn test_validate_stdout_output() {
+        let mut options = ConvertOptions {
+            output: Some(PathBuf::from("-")),
+            on_error: OnError::Skip,
+            ..ConvertOptions::default()
+        };
+        assert!(options.validate().is_ok());
+
+        options.on_error = OnError::Quarantine;
+        assert!(matches!(options.validate(), Err(Cc2pError::ConfigError(_))));
+    }
+
+    #[test]
+    fn test_delimiter() {
+        let mut options = ConvertOptions::default();
+        assert_eq!(options.delimiter_byte(), b',');
+        assert_eq!(options.delimiter_sequence(), None);
+
+        options.delimiter = "||".to_string();
+        assert!(options.validate().is_ok());
+        assert_eq!(options.delimiter_byte(), DELIMITER_SUBSTITUTE);
+        assert_eq!(options.delimiter_sequence(), Some(b"||".as_slice()));
+
+        options.delimiter = "§".to_string();
+        assert!(options.validate().is_ok());
+        assert_eq!(options.delimiter_sequence(), Some("§".as_bytes()));
+
+        for delimiter in ["", "\"", "a\nb"] {
+            options.delimiter = delimiter.to_string();
+            assert!(matches!(options.validate(), Err(Cc2pError::ConfigError(_))));
+        }
+    }
+}
@@ -0,0 +1,1117 @@
+use crate::archive::{open_member, split_member};
+use crate::decompress::decompress_reader;
+use crate::encoding::decode_reader;
+use crate::error::Cc2pError;
+use crate::number::normalize_number;
+use crate::options::{ConvertOptions, DEFAULT_BATCH_SIZE, DELIMITER_SUBSTITUTE, DialectBytes};
+use crate::replay::{Replay, ReplayReader};
+use crate::temporal::{normalize_timestamp, parse_date};
+use crate::utils::is_stdio;
+use arrow_array::cast::AsArray;
+use arrow_array::{Array, ArrayRef, BooleanArray, RecordBatch, StringArray};
+use arrow_csv::ReaderBuilder;
+use arrow_csv::reader::Decoder;
+use arrow_schema::{ArrowError, DataType, Field, Schema, SchemaRef};
+use csv::ByteRecord;
+use once_cell::sync::Lazy;
+use regex::Regex;
+use std::borrow::Cow;
+use std::cell::{Cell, RefCell};
+use std::io::{self, Read};
+use std::path::Path;
+use std::rc::Rc;
+use std::sync::Arc;
+
+/// Builds a CSV record reader configured with the dialect of the given options.
+///
+/// Schema inference reads records through this reader, which has the same dialect as the [`BatchReader`] of the
+/// conversion, so both always agree on how the file is split into fields.
+///
+/// # Returns
+///
+/// Returns the reader, or a `ConfigError` if a dialect character is not a single ASCII character.
+pub(crate) fn record_reader<R: Read>(reader: R, options: &ConvertOptions) -> Result<csv::Reader<R>, Cc2pError> {
+    Ok(csv_reader_builder(&options.dialect_bytes()?)
+        .has_headers(options.has_header)
+        .trim(if options.trim { csv::Trim::All } else { csv::Trim::None })
+        .from_reader(reader))
+}
+
+/// Returns a CSV reader builder with the delimiter, quote, escape, comment and terminator of a dialect.
+///
+/// The data is expected to come from [`open_input`], which already rewrote the delimiter and doubled quotes.
+fn csv_reader_builder(dialect: &DialectBytes) -> csv::ReaderBuilder {
+    let mut builder = csv::ReaderBuilder::new();
+    builder
+        .delimiter(dialect.delimiter)
+        .quote(dialect.quote)
+        .escape(dialect.escape)
+        .comment(dialect.comment)
+        .terminator(dialect.terminator.map_or(csv::Terminator::CRLF, csv::Terminator::Any));
+    builder
+}
+
+/// Rewrites a value into the form arrow-csv decodes as `data_type`.
+///
+/// Null values become empty values, in boolean columns the configured true and false values become `true` and
+/// `false`, in date and timestamp columns the values matching a custom format are rewritten in ISO 8601 form,
+/// and in numeric columns the values are rewritten without locale separators and symbols. Other values are
+/// returned unchanged.
+pub(crate) fn normalize_value<'a>(value: &'a str, data_type: &DataType, options: &ConvertOptions) -> Cow<'a, str> {
+    if options.is_null_value(value) {
+        return Cow::Borrowed("");
+    }
+
+    let normalized = match data_type {
+        DataType::Boolean => options.boolean_value(value).map(|b| b.to_string()),
+        DataType::Date32 | DataType::Date64 => parse_date(value, options),
+        DataType::Timestamp(_, _) => normalize_timestamp(value, options),
+        data_type if data_type.is_numeric() => normalize_number(value, options),
+        _ => None,
+    };
+    normalized.map_or(Cow::Borrowed(value), Cow::Owned)
+}
+
+/// How a CSV file is read.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum ReadPass {
+    /// The file is sampled, e.g. for schema inference, and read again later.
+    Sample,
+    /// The file is read for the last time, by the conversion.
+    Final,
+}
+
+/// Where the bytes of a CSV file are read from.
+#[derive(Clone, Copy)]
+pub(crate) enum Source<'r, 'a> {
+    /// The file or archive member at the path, opened again for every pass.
+    Path,
+    /// A stream that can only be read once, such as stdin. Sampling passes keep what they read in the [`Replay`].
+    Stream(&'r RefCell<Replay<'a>>),
+}
+
+/// Calls `f` with the source of `path`, which is a new [`Replay`] of stdin for `-`.
+pub(crate) fn with_source<T>(path: &Path, f: impl FnOnce(Source<'_, '_>) -> T) -> T {
+    if is_stdio(path) {
+        let stdin = RefCell::new(Replay::new(io::stdin()));
+        f(Source::Stream(&stdin))
+    } else {
+        f(Source::Path)
+    }
+}
+
+/// Opens a file, archive member or stream, decompresses it if needed and decodes its text to UTF-8 with the
+/// encoding of `options`, see [`decompress_reader`] and [`decode_reader`].
+///
+/// A stream keeps the bytes read by a [`ReadPass::Sample`] in memory so that the next pass can read them again.
+pub(crate) fn open_text<'r>(
+    path: &Path,
+    source: Source<'r, '_>,
+    options: &ConvertOptions,
+    pass: ReadPass,
+) -> Result<Box<dyn Read + 'r>, Cc2pError> {
+    decode_reader(open_bytes(path, source, pass)?, options).map_err(Cc2pError::FileError)
+}
+
+/// Opens a file, archive member or stream and decompresses it if needed.
+fn open_bytes<'r>(path: &Path, source: Source<'r, '_>, pass: ReadPass) -> Result<Box<dyn Read + 'r>, Cc2pError> {
+    let file: Box<dyn Read + 'r> = match source {
+        Source::Stream(replay) => Box::new(ReplayReader::new(replay, pass == ReadPass::Sample)),
+        Source::Path => match split_member(path) {
+            Some((archive, member)) => open_member(archive, &member).map_err(Cc2pError::FileError)?,
+            None => Box::new(std::fs::File::open(path).map_err(Cc2pError::FileError)?),
+        },
+    };
+    decompress_reader(file, path).map_err(Cc2pError::FileError)
+}
+
+/// Opens a CSV file for reading with the settings of `options`.
+///
+/// The text is decoded to UTF-8 first, see [`open_text`]. Delimiters that are not a single ASCII character and
+/// quotes that are not doubled are rewritten on the fly, see [`DialectReader`].
+pub(crate) fn open_input<'r>(
+    path: &Path,
+    source: Source<'r, '_>,
+    options: &ConvertOptions,
+    pass: ReadPass,
+) -> Result<Box<dyn Read + 'r>, Cc2pError> {
+    let text = open_text(path, source, options, pass)?;
+    rewrite_dialect(text, options)
+}
+
+/// Opens a CSV file for the conversion like [`open_input`], and counts the bytes read after decompression, before
+/// the text is decoded, in `count`.
+pub(crate) fn open_counted_input<'r>(
+    path: &Path,
+    source: Source<'r, '_>,
+    options: &ConvertOptions,
+    count: Rc<Cell<u64>>,
+) -> Result<Box<dyn Read + 'r>, Cc2pError> {
+    let bytes = CountingReader {
+        inner: open_bytes(path, source, ReadPass::Final)?,
+        count,
+    };
+    let text = decode_reader(bytes, options).map_err(Cc2pError::FileError)?;
+    rewrite_dialect(text, options)
+}
+
+/// Wraps the text of a CSV file in a [`DialectReader`] if the readers cannot handle its dialect.
+fn rewrite_dialect<'r>(text: Box<dyn Read + 'r>, options: &ConvertOptions) -> Result<Box<dyn Read + 'r>, Cc2pError> {
+    Ok(if options.delimiter_sequence().is_some() || !options.double_quote {
+        Box::new(DialectReader::new(text, options)?)
+    } else {
+        text
+    })
+}
+
+/// A reader that adds the number of bytes read from it to a counter that outlives it.
+struct CountingReader<R> {
+    inner: R,
+    count: Rc<Cell<u64>>,
+}
+
+impl<R: Read> Read for CountingReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let read = self.inner.read(buf)?;
+        self.count.set(self.count.get() + read as u64);
+        Ok(read)
+    }
+}
+
+/// Where the [`DialectReader`] is in the CSV syntax.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum QuoteState {
+    /// At the start of a record.
+    RecordStart,
+    /// At the start of a field.
+    FieldStart,
+    /// Inside an unquoted field.
+    Field,
+    /// Inside a quoted field.
+    Quoted,
+    /// After an escape character inside a quoted field.
+    Escaped,
+    /// After the closing quote of a quoted field.
+    Closed,
+    /// Inside a field that goes on after its closing quote, which stays quoted for the CSV readers.
+    Tail,
+    /// Inside a comment line.
+    Comment,
+}
+
+/// A reader that rewrites the dialect of a CSV file into one that both CSV readers understand.
+///
+/// A delimiter the readers cannot handle, such as `§` or `||`, is replaced by [`DELIMITER_SUBSTITUTE`], and
+/// without `double_quote` a quote right after a closing quote is kept as a literal quote. Delimiters inside quoted
+/// fields are kept. Reading fails if the data contains the substitute byte itself while a delimiter is replaced.
+pub(crate) struct DialectReader<R> {
+    inner: R,
+    delimiter: Vec<u8>,
+    replacement: u8,
+    quote: u8,
+    escape: Option<u8>,
+    comment: Option<u8>,
+    terminator: Option<u8>,
+    double_quote: bool,
+    state: QuoteState,
+    pending: Vec<u8>,
+    output: Vec<u8>,
+    position: usize,
+    eof: bool,
+}
+
+impl<R: Read> DialectReader<R> {
+    pub(crate) fn new(inner: R, options: &ConvertOptions) -> Result<Self, Cc2pError> {
+        let dialect = options.dialect_bytes()?;
+        Ok(DialectReader {
+            inner,
+            delimiter: options.delimiter.as_bytes().to_vec(),
+            replacement: dialect.delimiter,
+            quote: dialect.quote,
+            escape: dialect.escape,
+            comment: dialect.comment,
+            terminator: dialect.terminator,
+            double_quote: options.double_quote,
+            state: QuoteState::RecordStart,
+            pending: Vec::new(),
+            output: Vec::new(),
+            position: 0,
+            eof: false,
+        })
+    }
+
+    fn is_terminator(&self, byte: u8) -> bool {
+        match self.terminator {
+            Some(terminator) => byte == terminator,
+            None => byte == b'\n' || byte == b'\r',
+        }
+    }
+
+    /// Writes a byte of an unquoted field, doubling quotes and escapes in the tail of a quoted field.
+    fn push_literal(&mut self, byte: u8) {
+        if self.state == QuoteState::Tail && (byte == self.quote || Some(byte) == self.escape) {
+            self.output.push(byte);
+        }
+        self.output.push(byte);
+    }
+
+    /// Writes the bytes that may have started a delimiter and closes the tail of a quoted field.
+    fn end_field(&mut self) {
+        for byte in std::mem::take(&mut self.pending) {
+            self.push_literal(byte);
+        }
+        if self.state == QuoteState::Tail {
+            self.output.push(self.quote);
+        }
+    }
+
+    fn push(&mut self, byte: u8) -> io::Result<()> {
+        if byte == DELIMITER_SUBSTITUTE && self.replacement == DELIMITER_SUBSTITUTE {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!(
+                    "the file contains the byte 0x1F, which cannot be read with the delimiter '{}'",
+                    String::from_utf8_lossy(&self.delimiter)
+                ),
+            ));
+        }
+
+        match self.state {
+            QuoteState::Comment => {
+                if byte == b'\n' {
+                    self.state = QuoteState::RecordStart;
+                }
+                self.output.push(byte);
+                return Ok(());
+            }
+            QuoteState::Quoted => {
+                if byte == self.quote {
+                    self.state = QuoteState::Closed;
+                } else if Some(byte) == self.escape {
+                    self.state = QuoteState::Escaped;
+                }
+                self.output.push(byte);
+                return Ok(());
+            }
+            QuoteState::Escaped => {
+                self.state = QuoteState::Quoted;
+                self.output.push(byte);
+                return Ok(());
+            }
+            // Without double quotes the quote is a literal character of a field that goes on unquoted
+            QuoteState::Closed if byte == self.quote => {
+                self.state = if self.double_quote { QuoteState::Quoted } else { QuoteState::Tail };
+                self.output.push(byte);
+                return Ok(());
+            }
+            QuoteState::RecordStart if Some(byte) == self.comment => {
+                self.state = QuoteState::Comment;
+                self.output.push(byte);
+                return Ok(());
+            }
+            QuoteState::RecordStart | QuoteState::FieldStart if byte == self.quote => {
+                self.state = QuoteState::Quoted;
+                self.output.push(byte);
+                return Ok(());
+            }
+            _ => {}
+        }
+
+        if self.is_terminator(byte) {
+            self.end_field();
+            self.state = QuoteState::RecordStart;
+            self.output.push(byte);
+            return Ok(());
+        }
+
+        // Keep the bytes that may start a delimiter until it is complete or cannot match anymore
+        self.pending.push(byte);
+        while !self.delimiter.starts_with(&self.pending) {
+            let byte = self.pending.remove(0);
+            self.push_literal(byte);
+        }
+        if self.pending == self.delimiter {
+            self.pending.clear();
+            self.end_field();
+            self.state = QuoteState::FieldStart;
+            self.output.push(self.replacement);
+        } else if self.state != QuoteState::Tail {
+            self.state = QuoteState::Field;
+        }
+        Ok(())
+    }
+}
+
+impl<R: Read> Read for DialectReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        while self.position == self.output.len() && !self.eof {
+            self.output.clear();
+            self.position = 0;
+
+            let mut chunk = [0; 8192];
+            let read = self.inner.read(&mut chunk)?;
+            if read == 0 {
+                self.eof = true;
+                self.end_field();
+                self.state = QuoteState::RecordStart;
+            }
+            for &byte in &chunk[..read] {
+                self.push(byte)?;
+            }
+        }
+
+        let count = buf.len().min(self.output.len() - self.position);
+        buf[..count].copy_from_slice(&self.output[self.position..self.position + count]);
+        self.position += count;
+        Ok(count)
+    }
+}
+
+/// Returns `true` if the error only affects the record that was being read, so the reader can continue with the
+/// next record.
+pub(crate) fn is_record_error(error: &csv::Error) -> bool {
+    matches!(error.kind(), csv::ErrorKind::UnequalLengths { .. } | csv::ErrorKind::Utf8 { .. })
+}
+
+/// Describes what is wrong with a record, without the position that [`csv::Error`] includes in its message.
+pub(crate) fn record_error_message(error: &csv::Error) -> String {
+    match error.kind() {
+        csv::ErrorKind::UnequalLengths { expected_len, len, .. } => field_count_message(*len, *expected_len),
+        csv::ErrorKind::Utf8 { err, .. } => utf8_message(err.field()),
+        _ => error.to_string(),
+    }
+}
+
+fn field_count_message(len: u64, expected_len: u64) -> String {
+    format!("found a row with {} fields, expected {} fields", len, expected_len)
+}
+
+fn utf8_message(field: usize) -> String {
+    format!("invalid UTF-8 in field {}", field + 1)
+}
+
+/// Converts a CSV reading error into a [`Cc2pError`], keeping the line of malformed rows.
+pub(crate) fn to_read_error(error: csv::Error, path: &Path) -> Cc2pError {
+    match error.position() {
+        Some(position) if is_record_error(&error) => Cc2pError::RecordError {
+            path: path.to_path_buf(),
+            line: position.line(),
+            message: record_error_message(&error),
+        },
+        _ => Cc2pError::CsvError(error.to_string()),
+    }
+}
+
+/// Size of the chunks the [`BatchReader`] reads from its input.
+const READ_SIZE: usize = 64 * 1024;
+
+/// Matches the position of a value the arrow-csv decoder cannot parse: its column and its 0-based row.
+static VALUE_ERROR: Lazy<Regex> = Lazy::new(|| Regex::new(r"(?:^Error parsing|for) column (\d+) at line (\d+)").unwrap());
+
+/// Matches the position of a row the arrow-csv decoder cannot split into fields: its 1-based row.
+static ROW_ERROR: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^(?:incorrect number of fields|Encountered invalid UTF-8 data) for line (\d+)").unwrap());
+
+/// Why a row cannot be converted.
+pub(crate) enum RowError {
+    /// The row cannot be split into the columns of the file, e.g. it has another number of fields.
+    Malformed(String),
+
+    /// A value cannot be parsed with the type of its column.
+    InvalidValue {
+        /// The index of the column in the projected schema.
+        field: usize,
+
+        /// The value as it is in the CSV file.
+        value: String,
+
+        /// The value after applying the null, boolean, date and number settings.
+        normalized: String,
+    },
+}
+
+/// A row skipped by the [`BatchReader`] because it cannot be converted.
+pub(crate) struct BadRow {
+    /// The 1-based line of the row in the CSV file.
+    pub(crate) line: u64,
+
+    /// The raw bytes of the row, including the line terminator.
+    pub(crate) raw: Vec<u8>,
+
+    /// Why the row cannot be converted.
+    pub(crate) error: RowError,
+}
+
+/// Errors of [`BatchReader::next_batch`].
+pub(crate) enum BatchError {
+    /// A row cannot be converted, reading goes on with the next row.
+    BadRow(BadRow),
+
+    /// Any other error, the file cannot be read further.
+    Failed(Cc2pError),
+}
+
+impl From<Cc2pError> for BatchError {
+    fn from(e: Cc2pError) -> Self {
+        BatchError::Failed(e)
+    }
+}
+
+/// The row of a batch that cannot be converted.
+struct Failure {
+    /// The index of the row in the batch.
+    row: usize,
+
+    /// The index of the column in the projected schema whose value cannot be parsed, `None` for a malformed row.
+    field: Option<usize>,
+}
+
+/// Errors of a single attempt to read a batch.
+enum DecodeError {
+    /// A row of the batch cannot be converted.
+    Row(Failure),
+
+    /// Any other error.
+    Failed(Cc2pError),
+}
+
+impl From<Cc2pError> for DecodeError {
+    fn from(e: Cc2pError) -> Self {
+        DecodeError::Failed(e)
+    }
+}
+
+/// Reads the projected columns of a CSV file as record batches with the arrow-csv decoder.
+///
+/// Columns whose values [`normalize_value`] may rewrite are decoded as text and parsed once normalized, the other
+/// columns are parsed by the decoder. When a batch fails, the position in the error points at the failing row:
+/// the rows before it are decoded again as a shorter batch, and the row itself is returned as a [`BadRow`] so
+/// that reading can go on after it.
+pub(crate) struct BatchReader<R> {
+    input: R,
+    options: ConvertOptions,
+    dialect: DialectBytes,
+    schema: SchemaRef,
+    read_schema: SchemaRef,
+    projection: Vec<usize>,
+    normalized: Vec<bool>,
+    null_regex: Option<Regex>,
+    batch_size: usize,
+    decoder: Decoder,
+    decoded: usize,
+    shortened: bool,
+    buffer: Vec<u8>,
+    fed: usize,
+    offset: u64,
+    line: u64,
+    eof: bool,
+}
+
+impl<R: Read> BatchReader<R> {
+    /// Creates a reader and skips the header of the file.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - The CSV data, see [`open_input`].
+    /// * `file_schema` - The schema of all columns of the file.
+    /// * `projection` - The indices of the columns that are read, in ascending order.
+    /// * `options` - The CSV reading options.
+    pub(crate) fn new(input: R, file_schema: &Schema, projection: &[usize], options: &ConvertOptions) -> Result<Self, Cc2pError> {
+        let schema = Arc::new(file_schema.project(projection).map_err(|e| Cc2pError::SchemaError(e.to_string()))?);
+        let normalized: Vec<bool> = schema.fields().iter().map(|f| is_normalized(f.data_type(), options)).collect();
+
+        // Columns that are normalized first or not read at all are decoded as text
+        let fields: Vec<Field> = file_schema
+            .fields()
+            .iter()
+            .enumerate()
+            .map(|(i, field)| match projection.iter().position(|&p| p == i) {
+                Some(j) if !normalized[j] => field.as_ref().clone(),
+                _ => Field::new(field.name(), DataType::Utf8, true),
+            })
+            .collect();
+        let read_schema = Arc::new(Schema::new(fields));
+
+        let null_regex = if options.null_values.is_empty() {
+            None
+        } else {
+            let values: Vec<String> = options.null_values.iter().map(|value| regex::escape(value)).collect();
+            let pattern = format!("^(?:|{})$", values.join("|"));
+            Some(Regex::new(&pattern).map_err(|e| Cc2pError::ConfigError(e.to_string()))?)
+        };
+
+        // The header is read as a batch of its own, as text
+        let text_schema = Schema::new(
+            file_schema
+                .fields()
+                .iter()
+                .map(|field| Field::new(field.name(), DataType::Utf8, true))
+                .collect::<Vec<_>>(),
+        );
+        let dialect = options.dialect_bytes()?;
+        let decoder = decoder_builder(Arc::new(text_schema), &dialect, None, 1).build_decoder();
+
+        let batch_size = options.batch_size.unwrap_or(DEFAULT_BATCH_SIZE);
+        let mut reader = BatchReader {
+            input,
+            options: options.clone(),
+            dialect,
+            schema,
+            read_schema,
+            projection: projection.to_vec(),
+            normalized,
+            null_regex,
+            batch_size,
+            decoder,
+            decoded: 0,
+            shortened: false,
+            buffer: Vec::new(),
+            fed: 0,
+            offset: 0,
+            line: 1,
+            eof: false,
+        };
+        if options.has_header {
+            reader.decode().and_then(|_| reader.decoder.flush()).map_err(to_error)?;
+            reader.advance(reader.fed);
+        }
+        reader.reset_decoder(batch_size);
+        Ok(reader)
+    }
+
+    /// Returns the schema of the batches.
+    pub(crate) fn schema(&self) -> SchemaRef {
+        self.schema.clone()
+    }
+
+    /// Reads the next batch.
+    ///
+    /// # Returns
+    ///
+    /// Returns `None` at the end of the file, or a [`BatchError::BadRow`] for a row that cannot be converted once
+    /// the rows before it have been returned. The next call goes on with the row after it.
+    pub(crate) fn next_batch(&mut self) -> Result<Option<RecordBatch>, BatchError> {
+        loop {
+            let failure = match self.read_batch() {
+                Ok(batch) => {
+                    // Only the batch that ends before a bad row is shorter
+                    if self.shortened {
+                        self.shortened = false;
+                        self.reset_decoder(self.batch_size);
+                    }
+                    return Ok(batch);
+                }
+                Err(DecodeError::Row(failure)) => failure,
+                Err(DecodeError::Failed(e)) => return Err(BatchError::Failed(e)),
+            };
+
+            // Start over from the first row of the batch, which is still buffered
+            self.fed = 0;
+            if failure.row > 0 {
+                self.shortened = true;
+                self.reset_decoder(failure.row);
+                continue;
+            }
+            let bad_row = self.take_row(failure.field)?;
+            self.reset_decoder(self.batch_size);
+            return Err(BatchError::BadRow(bad_row));
+        }
+    }
+
+    /// Decodes and parses a batch.
+    fn read_batch(&mut self) -> Result<Option<RecordBatch>, DecodeError> {
+        if let Err(e) = self.decode() {
+            return Err(self.locate(e));
+        }
+        let batch = match self.decoder.flush() {
+            Ok(Some(batch)) => batch,
+            Ok(None) => return Ok(None),
+            Err(e) => return Err(self.locate(e)),
+        };
+
+        let batch = self.parse_columns(batch)?;
+        self.decoded += batch.num_rows();
+        self.advance(self.fed);
+        Ok(Some(batch))
+    }
+
+    /// Feeds the buffered input to the decoder until it holds a full batch or the input ends.
+    fn decode(&mut self) -> Result<(), ArrowError> {
+        while self.decoder.capacity() > 0 {
+            if self.fed == self.buffer.len() && !self.eof {
+                let start = self.buffer.len();
+                self.buffer.resize(start + READ_SIZE, 0);
+                let read = self
+                    .input
+                    .read(&mut self.buffer[start..])
+                    .map_err(|e| ArrowError::IoError(e.to_string(), e))?;
+                self.buffer.truncate(start + read);
+                self.eof = read == 0;
+            }
+
+            let decoded = self.decoder.decode(&self.buffer[self.fed..])?;
+            self.fed += decoded;
+            if decoded == 0 {
+                break;
+            }
+        }
+        Ok(())
+    }
+
+    /// Finds the row of the batch that an error of the decoder points at.
+    fn locate(&self, error: ArrowError) -> DecodeError {
+        let failure = match &error {
+            ArrowError::ParseError(message) => VALUE_ERROR.captures(message).and_then(|captures| {
+                let column: usize = captures[1].parse().ok()?;
+                let row: usize = captures[2].parse().ok()?;
+                Some(Failure {
+                    row: row.checked_sub(self.decoded)?,
+                    field: Some(self.projection.iter().position(|&p| p == column)?),
+                })
+            }),
+            ArrowError::CsvError(message) => ROW_ERROR.captures(message).and_then(|captures| {
+                let line: usize = captures[1].parse().ok()?;
+                Some(Failure {
+                    row: line.checked_sub(self.decoded + 1)?,
+                    field: None,
+                })
+            }),
+            _ => None,
+        };
+
+        // A message without a position the patterns know still tells where the batch starts
+        match (failure, error) {
+            (Some(failure), _) => DecodeError::Row(failure),
+            (None, error @ (ArrowError::ParseError(_) | ArrowError::CsvError(_))) => {
+                DecodeError::Failed(Cc2pError::CsvError(format!("{} (in the rows after byte {})", error, self.offset)))
+            }
+            (None, error) => DecodeError::Failed(to_error(error)),
+        }
+    }
+
+    /// Normalizes and parses the columns that were decoded as text.
+    fn parse_columns(&self, batch: RecordBatch) -> Result<RecordBatch, DecodeError> {
+        let mut columns = batch.columns().to_vec();
+        let mut failure: Option<Failure> = None;
+        for (field, column) in columns.iter_mut().enumerate() {
+            if !self.normalized[field] {
+                continue;
+            }
+
+            let data_type = self.schema.field(field).data_type();
+            let values: StringArray = column
+                .as_string::<i32>()
+                .iter()
+                .map(|value| {
+                    value
+                        .map(|value| self.normalize(value, data_type))
+                        .filter(|value| !value.is_empty())
+                })
+                .collect();
+            let parsed: ArrayRef = match data_type {
+                DataType::Boolean => Arc::new(values.iter().map(|value| value.and_then(parse_bool)).collect::<BooleanArray>()),
+                data_type => arrow_cast::cast(&values, data_type).map_err(|e| Cc2pError::CsvError(e.to_string()))?,
+            };
+
+            // arrow drops the digits beyond the scale of a decimal, which would silently lose precision
+            let scale = match data_type {
+                DataType::Decimal128(_, scale) | DataType::Decimal256(_, scale) => Some(*scale),
+                _ => None,
+            };
+            let invalid = (0..values.len())
+                .find(|&row| values.is_valid(row) && (parsed.is_null(row) || scale.is_some_and(|scale| !fits_scale(values.value(row), scale))));
+            if let Some(row) = invalid
+                && failure.as_ref().is_none_or(|failure| row < failure.row)
+            {
+                failure = Some(Failure { row, field: Some(field) });
+            }
+            *column = parsed;
+        }
+
+        if let Some(failure) = failure {
+            return Err(DecodeError::Row(failure));
+        }
+        RecordBatch::try_new(self.schema.clone(), columns).map_err(|e| DecodeError::Failed(Cc2pError::CsvError(e.to_string())))
+    }
+
+    /// Removes the first row from the buffer and describes why it cannot be converted.
+    fn take_row(&mut self, field: Option<usize>) -> Result<BadRow, Cc2pError> {
+        // Skip blank lines, and the `\n` of a `\r\n` terminator when the decoder stopped after the `\r`
+        let terminator = self.dialect.terminator;
+        let blank = self
+            .buffer
+            .iter()
+            .take_while(|&&b| terminator.map_or(b == b'\n' || b == b'\r', |t| b == t))
+            .count();
+        self.advance(blank);
+
+        let mut reader = csv_reader_builder(&self.dialect)
+            .has_headers(false)
+            .flexible(true)
+            .from_reader(self.buffer.as_slice());
+        let mut record = ByteRecord::new();
+        if !reader
+            .read_byte_record(&mut record)
+            .map_err(|e| Cc2pError::CsvError(e.to_string()))?
+        {
+            return Err(Cc2pError::CsvError("unexpected end of the file".to_string()));
+        }
+        let start = record.position().map_or(0, |p| p.byte()) as usize;
+        let mut end = reader.position().byte() as usize;
+        // Keep the whole terminator with the row
+        if terminator.is_none() && self.buffer[..end].ends_with(b"\r") && self.buffer.get(end) == Some(&b'\n') {
+            end += 1;
+        }
+        let line = self.line + record.position().map_or(1, |p| p.line()) - 1;
+
+        let error = match field {
+            Some(field) => {
+                let value = String::from_utf8_lossy(record.get(self.projection[field]).unwrap_or_default()).into_owned();
+                let normalized = self.normalize(&value, self.schema.field(field).data_type()).into_owned();
+                RowError::InvalidValue { field, value, normalized }
+            }
+            None if record.len() != self.read_schema.fields().len() => {
+                RowError::Malformed(field_count_message(record.len() as u64, self.read_schema.fields().len() as u64))
+            }
+            None => match record.iter().position(|value| std::str::from_utf8(value).is_err()) {
+                Some(field) => RowError::Malformed(utf8_message(field)),
+                None => RowError::Malformed("the row cannot be read".to_string()),
+            },
+        };
+
+        let raw = self.buffer[start..end].to_vec();
+        self.advance(end);
+        Ok(BadRow { line, raw, error })
+    }
+
+    /// Applies `trim` and [`normalize_value`] to a value.
+    fn normalize<'a>(&self, value: &'a str, data_type: &DataType) -> Cow<'a, str> {
+        let value = if self.options.trim { value.trim() } else { value };
+        normalize_value(value, data_type, &self.options)
+    }
+
+    /// Drops the first `count` bytes of the buffer, which have been converted or skipped.
+    fn advance(&mut self, count: usize) {
+        let terminator = self.dialect.terminator.unwrap_or(b'\n');
+        self.line += self.buffer[..count].iter().filter(|&&b| b == terminator).count() as u64;
+        self.offset += count as u64;
+        self.buffer.drain(..count);
+        self.fed = self.fed.saturating_sub(count);
+    }
+
+    /// Replaces the decoder by a new one that reads batches of `batch_size` rows.
+    fn reset_decoder(&mut self, batch_size: usize) {
+        self.decoder = decoder_builder(self.read_schema.clone(), &self.dialect, self.null_regex.as_ref(), batch_size)
+            .with_projection(self.projection.clone())
+            .build_decoder();
+        self.decoded = 0;
+    }
+}
+
+/// Builds an arrow-csv reader configured with a dialect, without a header.
+fn decoder_builder(schema: SchemaRef, dialect: &DialectBytes, null_regex: Option<&Regex>, batch_size: usize) -> ReaderBuilder {
+    let mut builder = ReaderBuilder::new(schema)
+        .with_header(false)
+        .with_delimiter(dialect.delimiter)
+        .with_quote(dialect.quote)
+        .with_batch_size(batch_size);
+    if let Some(escape) = dialect.escape {
+        builder = builder.with_escape(escape);
+    }
+    if let Some(comment) = dialect.comment {
+        builder = builder.with_comment(comment);
+    }
+    if let Some(terminator) = dialect.terminator {
+        builder = builder.with_terminator(terminator);
+    }
+    if let Some(null_regex) = null_regex {
+        builder = builder.with_null_regex(null_regex.clone());
+    }
+    builder
+}
+
+/// Returns `true` if [`normalize_value`] may rewrite the values of a column with the given options.
+fn is_normalized(data_type: &DataType, options: &ConvertOptions) -> bool {
+    match data_type {
+        _ if options.trim => true,
+        DataType::Boolean => !options.true_values.is_empty() || !options.false_values.is_empty(),
+        DataType::Date32 | DataType::Date64 => !options.date_formats.is_empty(),
+        DataType::Timestamp(_, _) => !options.timestamp_formats.is_empty() || options.timezone.is_some(),
+        // The scale of decimals is checked before they are parsed
+        DataType::Decimal128(_, _) | DataType::Decimal256(_, _) => true,
+        data_type if data_type.is_numeric() => {
+            options.decimal_separator != '.' || options.grouping_separator.is_some() || options.strip_number_symbols
+        }
+        _ => false,
+    }
+}
+
+/// Parses a boolean like arrow-csv does.
+fn parse_bool(value: &str) -> Option<bool> {
+    if value.eq_ignore_ascii_case("true") {
+        Some(true)
+    } else if value.eq_ignore_ascii_case("false") {
+        Some(false)
+    } else {
+        None
+    }
+}
+
+/// Returns `false` if a fixed-point value has more significant fractional digits than `scale`.
+fn fits_scale(value: &str, scale: i8) -> bool {
+    if value.contains(['e', 'E']) {
+        return true;
+    }
+
+    let fraction = value.split_once('.').map_or("", |(_, fraction)| fraction);
+    fraction.trim_end_matches('0').len() <= usize::try_from(scale).unwrap_or(0)
+}
+
+/// Converts an error of the decoder that does not point at a row into a [`Cc2pError`].
+fn to_error(error: ArrowError) -> Cc2pError {
+    match error {
+        ArrowError::IoError(_, e) => Cc2pError::FileError(e),
+        e => Cc2pError::CsvError(e.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use csv::StringRecord;
+
+    #[test]
+    fn test_normalize_value() {
+        let options = ConvertOptions {
+            null_values: vec!["NULL".to_string(), "\\N".to_string()],
+            true_values: vec!["yes".to_string()],
+            false_values: vec!["no".to_string()],
+            ..ConvertOptions::default()
+        };
+
+        assert_eq!(normalize_value("\\N", &DataType::Int64, &options), "");
+        assert_eq!(normalize_value("null", &DataType::Utf8, &options), "null");
+        assert_eq!(normalize_value("YES", &DataType::Boolean, &options), "true");
+        assert_eq!(normalize_value("no", &DataType::Boolean, &options), "false");
+        assert_eq!(normalize_value("no", &DataType::Utf8, &options), "no");
+
+        let options = ConvertOptions {
+            date_formats: vec!["%d/%m/%Y".to_string()],
+            ..options
+        };
+        assert_eq!(normalize_value("16/10/2026", &DataType::Date32, &options), "2026-10-16");
+        assert_eq!(normalize_value("16/10/2026", &DataType::Utf8, &options), "16/10/2026");
+
+        let options = ConvertOptions {
+            decimal_separator: ',',
+            grouping_separator: Some('.'),
+            ..options
+        };
+        assert_eq!(normalize_value("1.234,5", &DataType::Float64, &options), "1234.5");
+        assert_eq!(normalize_value("1.234,5", &DataType::Utf8, &options), "1.234,5");
+    }
+
+    #[test]
+    fn test_record_reader_trim() {
+        let options = ConvertOptions {
+            trim: true,
+            ..ConvertOptions::default()
+        };
+        let mut reader = record_reader(" id , name \n 1 ,\" a \" \n".as_bytes(), &options).unwrap();
+
+        assert_eq!(reader.headers().unwrap(), vec!["id", "name"]);
+        let record = reader.records().next().unwrap().unwrap();
+        assert_eq!(record, vec!["1", "a"]);
+    }
+
+    #[test]
+    fn test_record_reader_dialect() {
+        let options = ConvertOptions {
+            delimiter: ";".to_string(),
+            quote: '\'',
+            escape: Some('\\'),
+            double_quote: false,
+            terminator: Some('|'),
+            ..ConvertOptions::default()
+        };
+        let mut reader = record_reader("id;name|1;'it\\'s; fine'|2;plain|".as_bytes(), &options).unwrap();
+
+        assert_eq!(reader.headers().unwrap(), vec!["id", "name"]);
+        let records: Vec<StringRecord> = reader.records().map(|r| r.unwrap()).collect();
+        assert_eq!(records, vec![vec!["1", "it's; fine"], vec!["2", "plain"]]);
+
+        let options = ConvertOptions {
+            comment: Some('#'),
+            ..ConvertOptions::default()
+        };
+        let mut reader = record_reader("# exported today\nid,name\n1,a\n# skipped\n2,b\n".as_bytes(), &options).unwrap();
+        assert_eq!(reader.headers().unwrap(), vec!["id", "name"]);
+        assert_eq!(reader.records().count(), 2);
+
+        // Characters that do not fit in a single byte are rejected instead of being truncated
+        for quote in ['é', '“'] {
+            let options = ConvertOptions {
+                quote,
+                ..ConvertOptions::default()
+            };
+            assert!(matches!(
+                record_reader("a,b\n".as_bytes(), &options),
+                Err(Cc2pError::ConfigError(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn test_dialect_reader() {
+        let options = ConvertOptions {
+            delimiter: "||".to_string(),
+            ..ConvertOptions::default()
+        };
+        let data = "id||name||note\n1||\"a||b\"||c|d\n2|||x||\n";
+        let mut reader = record_reader(DialectReader::new(data.as_bytes(), &options).unwrap(), &options).unwrap();
+
+        assert_eq!(reader.headers().unwrap(), vec!["id", "name", "note"]);
+        let records: Vec<StringRecord> = reader.records().map(|r| r.unwrap()).collect();
+        assert_eq!(records, vec![vec!["1", "a||b", "c|d"], vec!["2", "|x", ""]]);
+
+        let options = ConvertOptions {
+            delimiter: "§".to_string(),
+            ..ConvertOptions::default()
+        };
+        let mut reader = record_reader(DialectReader::new("a§b\n1§2\n".as_bytes(), &options).unwrap(), &options).unwrap();
+        assert_eq!(reader.headers().unwrap(), vec!["a", "b"]);
+
+        let mut content = String::new();
+        let mut reader = DialectReader::new("a\u{1f}b".as_bytes(), &options).unwrap();
+        assert_eq!(reader.read_to_string(&mut content).unwrap_err().kind(), io::ErrorKind::InvalidData);
+
+        // Without double quotes a quote after the closing quote is a literal character
+        let options = ConvertOptions {
+            double_quote: false,
+            ..ConvertOptions::default()
+        };
+        let data = "id,name\n1,\"a\"\"b\"\n2,\"c\"d\"\"\n";
+        let mut reader = record_reader(DialectReader::new(data.as_bytes(), &options).unwrap(), &options).unwrap();
+        let records: Vec<StringRecord> = reader.records().map(|r| r.unwrap()).collect();
+        assert_eq!(records, vec![vec!["1", "a\"b\""], vec!["2", "cd\"\""]]);
+    }
+
+    fn batch(result: Result<Option<RecordBatch>, BatchError>) -> RecordBatch {
+        match result {
+            Ok(Some(batch)) => batch,
+            _ => panic!("expected a batch"),
+        }
+    }
+
+    fn bad_row(result: Result<Option<RecordBatch>, BatchError>) -> BadRow {
+        match result {
+            Err(BatchError::BadRow(row)) => row,
+            _ => panic!("expected a bad row"),
+        }
+    }
+
+    #[test]
+    fn test_batch_reader() {
+        let schema = Schema::new(vec![
+            Field::new("id", DataType::Int64, true),
+            Field::new("name", DataType::Utf8, true),
+            Field::new("flag", DataType::Boolean, true),
+        ]);
+        let options = ConvertOptions {
+            null_values: vec!["NULL".to_string()],
+            true_values: vec!["Y".to_string()],
+            false_values: vec!["N".to_string()],
+            ..ConvertOptions::default()
+        };
+        let data = "id,name,flag\n1,\"a, b\",Y\n2,c,NULL\nx,d,Y\n4,e\n5,f,maybe\n6,g,true\n";
+        let mut reader = BatchReader::new(data.as_bytes(), &schema, &[0, 2], &options).unwrap();
+
+        // The rows before the first bad row come as a shorter batch
+        let first = batch(reader.next_batch());
+        assert_eq!(first.num_rows(), 2);
+        assert_eq!(first.column(0).as_primitive::<arrow_array::types::Int64Type>().values(), &[1, 2]);
+        let flags = first.column(1).as_boolean();
+        assert!(flags.value(0));
+        assert!(flags.is_null(1));
+
+        let row = bad_row(reader.next_batch());
+        assert_eq!(row.line, 4);
+        assert_eq!(row.raw, b"x,d,Y\n");
+        assert!(matches!(row.error, RowError::InvalidValue { field: 0, ref value, .. } if value == "x"));
+
+        let row = bad_row(reader.next_batch());
+        assert_eq!(row.line, 5);
+        assert!(matches!(row.error, RowError::Malformed(ref message) if message == "found a row with 2 fields, expected 3 fields"));
+
+        let row = bad_row(reader.next_batch());
+        assert_eq!(row.line, 6);
+        assert!(matches!(row.error, RowError::InvalidValue { field: 1, ref value, .. } if value == "maybe"));
+
+        assert_eq!(batch(reader.next_batch()).num_rows(), 1);
+        assert!(matches!(reader.next_batch(), Ok(None)));
+    }
+
+    #[test]
+    fn test_batch_reader_terminator() {
+        let schema = Schema::new(vec![Field::new("id", DataType::Int64, true)]);
+        let options = ConvertOptions {
+            terminator: Some(';'),
+            ..ConvertOptions::default()
+        };
+        let mut reader = BatchReader::new("id;1;2;x;4;".as_bytes(), &schema, &[0], &options).unwrap();
+
+        assert_eq!(batch(reader.next_batch()).num_rows(), 2);
+        let row = bad_row(reader.next_batch());
+        assert_eq!(row.line, 4);
+        assert_eq!(row.raw, b"x;");
+        assert_eq!(batch(reader.next_batch()).num_rows(), 1);
+    }
+
+    #[test]
+    fn test_decoder_error_messages() {
+        // The positions of bad rows are read from the messages of arrow-csv, this fails if their format changes
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("id", DataType::Int64, true),
+            Field::new("name", DataType::Utf8, true),
+        ]));
+        let decode = |data: &[u8]| {
+            let mut decoder = arrow_csv::ReaderBuilder::new(schema.clone()).build_decoder();
+            match decoder.decode(data).and_then(|_| decoder.flush()) {
+                Err(ArrowError::ParseError(message) | ArrowError::CsvError(message)) => message,
+                result => panic!("{:?}", result),
+            }
+        };
+
+        let message = decode(b"1,a\nx,b\n");
+        let captures = VALUE_ERROR.captures(&message).unwrap_or_else(|| panic!("{}", message));
+        assert_eq!((&captures[1], &captures[2]), ("0", "1"));
+
+        let message = decode(b"1,a\n2\n");
+        let captures = ROW_ERROR.captures(&message).unwrap_or_else(|| panic!("{}", message));
+        assert_eq!(&captures[1], "2");
+
+        let message = decode(b"1,a\n2,\xff\n");
+        let captures = ROW_ERROR.captures(&message).unwrap_or_else(|| panic!("{}", message));
+        assert_eq!(&captures[1], "2");
+    }
+
+    #[test]
+    fn test_batch_reader_decimals() {
+        let schema = Schema::new(vec![Field::new("amount", DataType::Decimal128(6, 2), true)]);
+        let options = ConvertOptions {
+            has_header: false,
+            ..ConvertOptions::default()
+        };
+        let mut reader = BatchReader::new("1234.50\r\n1.2500\r\n1.255\r\n".as_bytes(), &schema, &[0], &options).unwrap();
+
+        let amounts = batch(reader.next_batch());
+        assert_eq!(
+            amounts.column(0).as_primitive::<arrow_array::types::Decimal128Type>().values(),
+            &[123450, 125]
+        );
+        let row = bad_row(reader.next_batch());
+        assert_eq!(row.line, 3);
+        assert_eq!(row.raw, b"1.255\r\n");
+        assert!(matches!(row.error, RowError::InvalidValue { ref normalized, .. } if normalized == "1.255"));
+    }
+}
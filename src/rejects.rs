@@ -0,0 +1,155 @@
+use crate::error::{Cc2pError, Result};
+use crate::options::{ConvertOptions, DELIMITER_SUBSTITUTE, OnError};
+use std::borrow::Cow;
+use std::fs::File;
+use std::path::{Path, PathBuf};
+
+/// Returns the path of the rejects file written next to a Parquet file, e.g. `data.rejects.csv` for `data.parquet`.
+pub fn rejects_path(target_file: &Path) -> PathBuf {
+    target_file.with_extension("rejects.csv")
+}
+
+/// Counts the rows dropped during a conversion and, in [`OnError::Quarantine`] mode, writes them to the rejects file.
+///
+/// The rejects file is a CSV file with the columns `line`, `error` and `raw`, where `raw` holds the offending
+/// line exactly as it was read. It is only created once the first row is rejected.
+pub(crate) struct RejectWriter {
+    path: PathBuf,
+    terminator: Option<u8>,
+    delimiter: Option<Vec<u8>>,
+    quarantine: bool,
+    max_bad_rows: Option<usize>,
+    count: usize,
+    writer: Option<csv::Writer<File>>,
+}
+
+impl RejectWriter {
+    pub(crate) fn new(path: PathBuf, options: &ConvertOptions) -> Result<Self> {
+        Ok(RejectWriter {
+            path,
+            terminator: options.dialect_bytes()?.terminator,
+            delimiter: options.delimiter_sequence().map(|d| d.to_vec()),
+            quarantine: options.on_error == OnError::Quarantine,
+            max_bad_rows: options.max_bad_rows,
+            count: 0,
+            writer: None,
+        })
+    }
+
+    /// Records a rejected row.
+    ///
+    /// # Arguments
+    ///
+    /// * `line` - The 1-based line of the row in the CSV file.
+    /// * `raw` - The raw bytes of the row, including the line terminator.
+    /// * `error` - Why the row was rejected.
+    ///
+    /// # Returns
+    ///
+    /// Returns a `TooManyBadRows` error once more rows than `max_bad_rows` have been rejected.
+    pub(crate) fn reject(&mut self, line: u64, raw: &[u8], error: &str) -> Result<()> {
+        self.count += 1;
+        if let Some(max_bad_rows) = self.max_bad_rows
+            && self.count > max_bad_rows
+        {
+            return Err(Cc2pError::TooManyBadRows {
+                limit: max_bad_rows,
+                line,
+                message: error.to_string(),
+            });
+        }
+
+        if !self.quarantine {
+            return Ok(());
+        }
+
+        let writer = match &mut self.writer {
+            Some(writer) => writer,
+            None => {
+                let mut writer = csv::Writer::from_writer(File::create(&self.path).map_err(Cc2pError::FileError)?);
+                writer.write_record(["line", "error", "raw"]).map_err(to_csv_error)?;
+                self.writer.insert(writer)
+            }
+        };
+
+        let raw = match self.terminator {
+            Some(terminator) => raw.strip_suffix(&[terminator]).unwrap_or(raw),
+            None => {
+                let raw = raw.strip_suffix(b"\n").unwrap_or(raw);
+                raw.strip_suffix(b"\r").unwrap_or(raw)
+            }
+        };
+        // Restore a delimiter that was substituted while reading
+        let raw = match &self.delimiter {
+            Some(delimiter) => Cow::Owned(raw.iter().fold(Vec::with_capacity(raw.len()), |mut out, &b| {
+                if b == DELIMITER_SUBSTITUTE {
+                    out.extend_from_slice(delimiter);
+                } else {
+                    out.push(b);
+                }
+                out
+            })),
+            None => Cow::Borrowed(raw),
+        };
+        writer
+            .write_record([line.to_string().as_bytes(), error.as_bytes(), &raw])
+            .map_err(to_csv_error)
+    }
+
+    /// Flushes the rejects file.
+    ///
+    /// # Returns
+    ///
+    /// Returns the number of rejected rows.
+    pub(crate) fn finish(self) -> Result<usize> {
+        if let Some(mut writer) = self.writer {
+            writer.flush().map_err(Cc2pError::FileError)?;
+        }
+        Ok(self.count)
+    }
+}
+
+fn to_csv_error(e: csv::Error) -> Cc2pError {
+    Cc2pError::CsvError(e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rejects_path() {
+        assert_eq!(
+            rejects_path(Path::new("data/sales.parquet")),
+            PathBuf::from("data/sales.rejects.csv")
+        );
+    }
+
+    #[test]
+    fn test_reject_writer() {
+        let mut path = std::env::temp_dir();
+        path.push("temp_reject_writer_test.rejects.csv");
+        let options = ConvertOptions {
+            on_error: OnError::Quarantine,
+            max_bad_rows: Some(2),
+            ..ConvertOptions::default()
+        };
+
+        let mut rejects = RejectWriter::new(path.clone(), &options).unwrap();
+        rejects.reject(3, b"2,\"a,b\"\r\n", "bad value").unwrap();
+        rejects.reject(7, b"oops\n", "wrong number of fields").unwrap();
+        assert!(matches!(
+            rejects.reject(9, b"", "bad value"),
+            Err(Cc2pError::TooManyBadRows { limit: 2, line: 9, .. })
+        ));
+        rejects.finish().unwrap();
+
+        let content = std::fs::read_to_string(&path).unwrap();
+        assert_eq!(
+            content,
+            "line,error,raw\n3,bad value,\"2,\"\"a,b\"\"\"\n7,wrong number of fields,oops\n"
+        );
+
+        std::fs::remove_file(path).unwrap();
+    }
+}
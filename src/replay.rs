@@ -0,0 +1,95 @@
+use std::cell::RefCell;
+use std::io::{self, Read};
+
+/// A stream that can be read several times from the start, as long as the readers before the last one only read
+/// a prefix of it.
+///
+/// Schema inference and dialect detection read the head of a file once more before the conversion reads all of
+/// it. For stdin the head is kept in memory and replayed to the next reader. The caller owns the stream and passes
+/// it to every reader, so each conversion of stdin starts with an empty buffer.
+pub(crate) struct Replay<'a> {
+    source: Box<dyn Read + 'a>,
+    prefix: Vec<u8>,
+    /// Number of bytes read from the source, more than the prefix once a reader did not keep them.
+    position: usize,
+}
+
+impl<'a> Replay<'a> {
+    pub(crate) fn new(source: impl Read + 'a) -> Self {
+        Replay {
+            source: Box::new(source),
+            prefix: Vec::new(),
+            position: 0,
+        }
+    }
+}
+
+/// A reader of a [`Replay`] stream.
+pub(crate) struct ReplayReader<'r, 'a> {
+    replay: &'r RefCell<Replay<'a>>,
+    position: usize,
+    record: bool,
+}
+
+impl<'r, 'a> ReplayReader<'r, 'a> {
+    /// Creates a reader from the start of the stream.
+    ///
+    /// # Arguments
+    ///
+    /// * `replay` - The stream.
+    /// * `record` - Whether the bytes read are kept for the next reader. A reader that does not keep them must be
+    ///   the last one.
+    pub(crate) fn new(replay: &'r RefCell<Replay<'a>>, record: bool) -> Self {
+        ReplayReader {
+            replay,
+            position: 0,
+            record,
+        }
+    }
+}
+
+impl Read for ReplayReader<'_, '_> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let mut replay = self.replay.borrow_mut();
+        if self.position < replay.prefix.len() {
+            let count = buf.len().min(replay.prefix.len() - self.position);
+            buf[..count].copy_from_slice(&replay.prefix[self.position..self.position + count]);
+            self.position += count;
+            return Ok(count);
+        }
+        if self.position != replay.position {
+            return Err(io::Error::other("the stream cannot be read again after the conversion has read it"));
+        }
+
+        let read = replay.source.read(buf)?;
+        if self.record {
+            replay.prefix.extend_from_slice(&buf[..read]);
+        }
+        replay.position += read;
+        self.position += read;
+        Ok(read)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_replay() {
+        let replay = RefCell::new(Replay::new(io::Cursor::new(b"id\n1\n2\n".to_vec())));
+
+        let mut head = [0; 3];
+        ReplayReader::new(&replay, true).read_exact(&mut head).unwrap();
+        assert_eq!(&head, b"id\n");
+
+        let mut content = String::new();
+        ReplayReader::new(&replay, false).read_to_string(&mut content).unwrap();
+        assert_eq!(content, "id\n1\n2\n");
+
+        // Only the recorded prefix is left for another reader
+        let mut content = Vec::new();
+        assert!(ReplayReader::new(&replay, false).read_to_end(&mut content).is_err());
+        assert_eq!(content, b"id\n");
+    }
+}
@@ -0,0 +1,202 @@
+use crate::conversion::ConversionStats;
+use crate::dialect::Dialect;
+use crate::error::{Cc2pError, Result};
+use serde::Serialize;
+use std::path::Path;
+use std::time::Duration;
+
+/// Outcome of the conversion of a single file.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FileStatus {
+    /// The file was converted.
+    Ok,
+    /// The conversion of the file failed.
+    Failed,
+    /// The file was not converted because its Parquet file already exists, see `--overwrite`.
+    Skipped,
+}
+
+/// Summary of the conversion of a single file.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct FileReport {
+    /// The path of the CSV file.
+    pub path: String,
+
+    /// Whether the file was converted.
+    pub status: FileStatus,
+
+    /// The kind of error if the conversion failed, see [`Cc2pError::kind`].
+    pub error_kind: Option<&'static str>,
+
+    /// The error message if the conversion failed.
+    pub error: Option<String>,
+
+    /// Number of rows written to the Parquet file.
+    pub rows_written: Option<u64>,
+
+    /// Size of the CSV file on disk in bytes, compressed if it is, or `None` for archive members and stdin.
+    pub input_bytes: Option<u64>,
+
+    /// Number of CSV bytes read after decompression, see [`ConversionStats::input_bytes`], if the file was converted.
+    pub decompressed_bytes: Option<u64>,
+
+    /// Size of the Parquet file in bytes.
+    pub output_bytes: Option<u64>,
+
+    /// Time spent on the file in milliseconds.
+    pub duration_ms: u128,
+
+    /// The dialect detected in the CSV file by `--auto`.
+    pub dialect: Option<Dialect>,
+}
+
+impl FileReport {
+    /// Creates the summary of a conversion from its result.
+    ///
+    /// The input size is read from the file system, and left empty if the file is missing.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - The path of the CSV file.
+    /// * `result` - The result of the conversion.
+    /// * `duration` - Time spent on the file.
+    pub fn new(input: &Path, result: &Result<ConversionStats>, duration: Duration) -> Self {
+        let path = input.display().to_string();
+        let input_bytes = std::fs::metadata(input).ok().map(|m| m.len());
+        match result {
+            Ok(stats) => FileReport {
+                path,
+                status: FileStatus::Ok,
+                error_kind: None,
+                error: None,
+                rows_written: Some(stats.rows_written),
+                input_bytes,
+                decompressed_bytes: Some(stats.input_bytes),
+                output_bytes: Some(stats.output_bytes),
+                duration_ms: duration.as_millis(),
+                dialect: stats.dialect,
+            },
+            Err(e) => FileReport {
+                path,
+                status: match e {
+                    Cc2pError::OutputExists(_) => FileStatus::Skipped,
+                    _ => FileStatus::Failed,
+                },
+                error_kind: Some(e.kind()),
+                error: Some(e.to_string()),
+                rows_written: None,
+                input_bytes,
+                decompressed_bytes: None,
+                output_bytes: None,
+                duration_ms: duration.as_millis(),
+                dialect: None,
+            },
+        }
+    }
+}
+
+/// Summary of a batch run, written by `--report` as JSON.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct RunReport {
+    /// Number of files that were converted.
+    pub succeeded: usize,
+
+    /// Number of files whose conversion failed.
+    pub failed: usize,
+
+    /// Number of files that were skipped because their Parquet file already exists.
+    pub skipped: usize,
+
+    /// Total time of the run in milliseconds.
+    pub duration_ms: u128,
+
+    /// One entry per file, sorted by path.
+    pub files: Vec<FileReport>,
+}
+
+impl RunReport {
+    /// Creates the summary of a run from the summaries of its files.
+    pub fn new(mut files: Vec<FileReport>, duration: Duration) -> Self {
+        files.sort_by(|a, b| a.path.cmp(&b.path));
+        let failed = files.iter().filter(|f| f.status == FileStatus::Failed).count();
+        let skipped = files.iter().filter(|f| f.status == FileStatus::Skipped).count();
+
+        RunReport {
+            succeeded: files.len() - failed - skipped,
+            failed,
+            skipped,
+            duration_ms: duration.as_millis(),
+            files,
+        }
+    }
+
+    /// Writes the summary as pretty-printed JSON.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The path of the report file.
+    ///
+    /// # Returns
+    ///
+    /// Returns `Ok(())` if the report was written, otherwise returns an error.
+    pub fn write(&self, path: &Path) -> Result<()> {
+        let content = serde_json::to_string_pretty(self).map_err(|e| Cc2pError::Other(e.to_string()))?;
+        std::fs::write(path, content + "\n").map_err(Cc2pError::FileError)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow_schema::Schema;
+    use std::sync::Arc;
+
+    #[test]
+    fn test_run_report() {
+        let failed = FileReport::new(
+            Path::new("testdata/sample.csv"),
+            &Err(Cc2pError::CsvError("broken".to_string())),
+            Duration::from_millis(5),
+        );
+        assert_eq!(failed.error_kind, Some("csv"));
+        assert!(failed.input_bytes.is_some());
+        assert_eq!(failed.rows_written, None);
+
+        let stats = ConversionStats {
+            rows_read: 3,
+            rows_written: 2,
+            rows_rejected: 1,
+            row_groups: 1,
+            input_bytes: 100,
+            output_bytes: 50,
+            duration: Duration::from_millis(1),
+            schema: Arc::new(Schema::empty()),
+            dialect: None,
+        };
+        let ok = FileReport::new(Path::new("testdata/other.csv"), &Ok(stats), Duration::from_millis(1));
+        assert_eq!(ok.status, FileStatus::Ok);
+        assert_eq!(ok.rows_written, Some(2));
+        assert_eq!(ok.output_bytes, Some(50));
+        // The file does not exist, only the size read by the conversion is known
+        assert_eq!(ok.input_bytes, None);
+        assert_eq!(ok.decompressed_bytes, Some(100));
+
+        let skipped = FileReport::new(
+            Path::new("testdata/skipped.csv"),
+            &Err(Cc2pError::OutputExists("testdata/skipped.parquet".into())),
+            Duration::ZERO,
+        );
+        assert_eq!(skipped.status, FileStatus::Skipped);
+
+        let report = RunReport::new(vec![failed, ok, skipped], Duration::from_millis(10));
+        assert_eq!(report.succeeded, 1);
+        assert_eq!(report.failed, 1);
+        assert_eq!(report.skipped, 1);
+        assert_eq!(report.files[0].path, "testdata/other.csv");
+
+        let json: serde_json::Value = serde_json::to_value(&report).unwrap();
+        assert_eq!(json["files"][1]["status"], "failed");
+        assert_eq!(json["files"][1]["error_kind"], "csv");
+    }
+}
@@ -0,0 +1,350 @@
+use crate::error::{Cc2pError, Result};
+use crate::options::SchemaMode;
+use arrow_array::types::{Decimal128Type, Decimal256Type, validate_decimal_precision_and_scale};
+use arrow_schema::{DataType, Field, Schema};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+
+/// File formats supported for schema files.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum SchemaFormat {
+    /// JSON schema file.
+    #[default]
+    Json,
+    /// TOML schema file.
+    Toml,
+}
+
+impl SchemaFormat {
+    /// Detects the schema format from the extension of a file path.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The path of the schema file.
+    ///
+    /// # Returns
+    ///
+    /// Returns the format, or a `ConfigError` if the extension is neither `json` nor `toml`.
+    pub fn from_path(path: &Path) -> Result<Self> {
+        match path.extension().and_then(|ext| ext.to_str()).map(|ext| ext.to_ascii_lowercase()) {
+            Some(ext) if ext == "json" => Ok(SchemaFormat::Json),
+            Some(ext) if ext == "toml" => Ok(SchemaFormat::Toml),
+            _ => Err(Cc2pError::ConfigError(format!(
+                "cannot detect schema format of '{}', expected a .json or .toml file",
+                path.display()
+            ))),
+        }
+    }
+}
+
+/// Serialized form of a schema file.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct SchemaFile {
+    fields: Vec<SchemaField>,
+}
+
+/// A single column of a schema file.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct SchemaField {
+    name: String,
+    #[serde(rename = "type")]
+    data_type: String,
+    #[serde(default = "default_nullable")]
+    nullable: bool,
+}
+
+fn default_nullable() -> bool {
+    true
+}
+
+/// Parses the content of a schema file into an Arrow schema.
+///
+/// Each field has a `name`, an Arrow `type` such as `Int64`, `Utf8` or `Timestamp(Second, None)`,
+/// and an optional `nullable` flag which defaults to `true`.
+///
+/// # Arguments
+///
+/// * `content` - The content of the schema file.
+/// * `format` - The format of the content.
+///
+/// # Examples
+///
+/// ```rust
+/// use arrow_schema::DataType;
+/// use cc2p::schema::{SchemaFormat, parse_schema};
+///
+/// let content = r#"{"fields": [{"name": "id", "type": "Int64", "nullable": false}]}"#;
+/// let schema = parse_schema(content, SchemaFormat::Json).unwrap();
+/// assert_eq!(schema.field(0).data_type(), &DataType::Int64);
+/// assert!(!schema.field(0).is_nullable());
+/// ```
+///
+/// # Returns
+///
+/// Returns the schema, or a `SchemaError` if the content is invalid.
+pub fn parse_schema(content: &str, format: SchemaFormat) -> Result<Schema> {
+    let file: SchemaFile = match format {
+        SchemaFormat::Json => serde_json::from_str(content).map_err(|e| Cc2pError::SchemaError(e.to_string()))?,
+        SchemaFormat::Toml => toml::from_str(content).map_err(|e| Cc2pError::SchemaError(e.to_string()))?,
+    };
+
+    let mut fields = Vec::with_capacity(file.fields.len());
+    for field in file.fields {
+        let data_type: DataType = field
+            .data_type
+            .parse()
+            .map_err(|e| Cc2pError::SchemaError(format!("invalid type '{}' for column '{}': {}", field.data_type, field.name, e)))?;
+        let decimal = match data_type {
+            DataType::Decimal128(precision, scale) => validate_decimal_precision_and_scale::<Decimal128Type>(precision, scale),
+            DataType::Decimal256(precision, scale) => validate_decimal_precision_and_scale::<Decimal256Type>(precision, scale),
+            _ => Ok(()),
+        };
+        decimal.map_err(|e| Cc2pError::SchemaError(format!("invalid type '{}' for column '{}': {}", field.data_type, field.name, e)))?;
+        fields.push(Field::new(field.name, data_type, field.nullable));
+    }
+
+    Ok(Schema::new(fields))
+}
+
+/// Loads a JSON or TOML schema file into an Arrow schema.
+///
+/// The format is detected from the file extension.
+///
+/// # Arguments
+///
+/// * `path` - The path of the schema file.
+///
+/// # Returns
+///
+/// Returns the schema, or an error if the file cannot be read or parsed.
+pub fn load_schema(path: &Path) -> Result<Schema> {
+    let format = SchemaFormat::from_path(path)?;
+    let content = std::fs::read_to_string(path).map_err(Cc2pError::FileError)?;
+    parse_schema(&content, format).map_err(|e| Cc2pError::SchemaError(format!("{}: {}", path.display(), e)))
+}
+
+/// Serializes an Arrow schema in the schema file format accepted by [`parse_schema`].
+///
+/// # Arguments
+///
+/// * `schema` - The schema to serialize.
+/// * `format` - The output format.
+///
+/// # Examples
+///
+/// ```rust
+/// use arrow_schema::{DataType, Field, Schema};
+/// use cc2p::schema::{SchemaFormat, parse_schema, schema_to_string};
+///
+/// let schema = Schema::new(vec![Field::new("id", DataType::Int64, true)]);
+/// let content = schema_to_string(&schema, SchemaFormat::Toml).unwrap();
+/// assert_eq!(parse_schema(&content, SchemaFormat::Toml).unwrap(), schema);
+/// ```
+///
+/// # Returns
+///
+/// Returns the serialized schema, or a `SchemaError` if serialization fails.
+pub fn schema_to_string(schema: &Schema, format: SchemaFormat) -> Result<String> {
+    let file = SchemaFile {
+        fields: schema
+            .fields()
+            .iter()
+            .map(|f| SchemaField {
+                name: f.name().clone(),
+                data_type: f.data_type().to_string(),
+                nullable: f.is_nullable(),
+            })
+            .collect(),
+    };
+
+    match format {
+        SchemaFormat::Json => serde_json::to_string_pretty(&file)
+            .map(|content| content + "\n")
+            .map_err(|e| Cc2pError::SchemaError(e.to_string())),
+        SchemaFormat::Toml => toml::to_string(&file).map_err(|e| Cc2pError::SchemaError(e.to_string())),
+    }
+}
+
+/// Applies a schema file to an inferred schema.
+///
+/// In [`SchemaMode::Override`] each field of `schema` replaces the type and nullability of the inferred column
+/// with the same name. Names are matched against the header names as well as the deduplicated names produced by
+/// [`remove_deduplicate_columns`](crate::conversion::remove_deduplicate_columns), so `column_1` refers to the
+/// first empty header. In [`SchemaMode::Replace`] the schema is used as is.
+///
+/// # Arguments
+///
+/// * `inferred` - The schema inferred from the CSV file, before deduplication.
+/// * `schema` - The schema loaded from a schema file.
+/// * `mode` - How the schema file is applied.
+///
+/// # Returns
+///
+/// Returns the resulting schema, or a `SchemaError` if a field of `schema` does not match any column.
+pub fn apply_schema(inferred: Schema, schema: &Schema, mode: SchemaMode) -> Result<Schema> {
+    if mode == SchemaMode::Replace {
+        return Ok(schema.clone());
+    }
+
+    let overrides: HashMap<&str, &Field> = schema.fields().iter().map(|f| (f.name().as_str(), f.as_ref())).collect();
+    let deduplicated = crate::conversion::remove_deduplicate_columns(inferred.clone());
+
+    let mut matched = HashSet::new();
+    let mut fields = Vec::with_capacity(inferred.fields().len());
+    for (field, deduplicated_field) in inferred.fields().iter().zip(deduplicated.fields()) {
+        let replacement = [deduplicated_field.name(), field.name()]
+            .into_iter()
+            .find_map(|name| overrides.get_key_value(name.as_str()));
+
+        match replacement {
+            Some((name, replacement)) => {
+                matched.insert(*name);
+                fields.push(
+                    field
+                        .as_ref()
+                        .clone()
+                        .with_data_type(replacement.data_type().clone())
+                        .with_nullable(replacement.is_nullable()),
+                );
+            }
+            None => fields.push(field.as_ref().clone()),
+        }
+    }
+
+    let mut unknown: Vec<&str> = overrides.keys().filter(|name| !matched.contains(*name)).copied().collect();
+    if !unknown.is_empty() {
+        unknown.sort_unstable();
+        return Err(Cc2pError::SchemaError(format!(
+            "schema refers to unknown columns: {}",
+            unknown.join(", ")
+        )));
+    }
+
+    Ok(Schema::new_with_metadata(fields, inferred.metadata().clone()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow_schema::TimeUnit;
+
+    #[test]
+    fn test_parse_schema_json() {
+        let content = r#"{
+            "fields": [
+                {"name": "id", "type": "Int64", "nullable": false},
+                {"name": "price", "type": "Decimal128(10, 2)"},
+                {"name": "created", "type": "Timestamp(Second, None)"}
+            ]
+        }"#;
+        let schema = parse_schema(content, SchemaFormat::Json).unwrap();
+
+        assert_eq!(schema.fields().len(), 3);
+        assert!(!schema.field(0).is_nullable());
+        assert_eq!(schema.field(1).data_type(), &DataType::Decimal128(10, 2));
+        assert!(schema.field(1).is_nullable());
+        assert_eq!(schema.field(2).data_type(), &DataType::Timestamp(TimeUnit::Second, None));
+    }
+
+    #[test]
+    fn test_parse_schema_toml() {
+        let content = r#"
+            [[fields]]
+            name = "id"
+            type = "Int64"
+
+            [[fields]]
+            name = "name"
+            type = "Utf8"
+            nullable = false
+        "#;
+        let schema = parse_schema(content, SchemaFormat::Toml).unwrap();
+
+        assert_eq!(schema.field(0).data_type(), &DataType::Int64);
+        assert_eq!(schema.field(1).name(), "name");
+        assert!(!schema.field(1).is_nullable());
+    }
+
+    #[test]
+    fn test_parse_schema_invalid_type() {
+        let content = r#"{"fields": [{"name": "id", "type": "Integer"}]}"#;
+        assert!(matches!(parse_schema(content, SchemaFormat::Json), Err(Cc2pError::SchemaError(_))));
+
+        let content = r#"{"fields": [{"name": "price", "type": "Decimal128(40, 2)"}]}"#;
+        assert!(matches!(parse_schema(content, SchemaFormat::Json), Err(Cc2pError::SchemaError(_))));
+
+        let content = r#"{"fields": [{"name": "price", "type": "Decimal128(4, 6)"}]}"#;
+        assert!(matches!(parse_schema(content, SchemaFormat::Json), Err(Cc2pError::SchemaError(_))));
+    }
+
+    #[test]
+    fn test_schema_to_string_round_trip() {
+        let schema = Schema::new(vec![
+            Field::new("id", DataType::Int64, false),
+            Field::new("price", DataType::Float64, true),
+            Field::new("created", DataType::Timestamp(TimeUnit::Second, None), true),
+            Field::new("day", DataType::Date32, true),
+            Field::new("active", DataType::Boolean, true),
+            Field::new("name", DataType::Utf8, true),
+            Field::new("amount", DataType::Decimal128(12, 2), true),
+        ]);
+
+        for format in [SchemaFormat::Json, SchemaFormat::Toml] {
+            let content = schema_to_string(&schema, format).unwrap();
+            assert_eq!(parse_schema(&content, format).unwrap(), schema);
+        }
+    }
+
+    #[test]
+    fn test_schema_format_from_path() {
+        assert_eq!(SchemaFormat::from_path(Path::new("schema.json")).unwrap(), SchemaFormat::Json);
+        assert_eq!(SchemaFormat::from_path(Path::new("schema.TOML")).unwrap(), SchemaFormat::Toml);
+        assert!(SchemaFormat::from_path(Path::new("schema.yaml")).is_err());
+    }
+
+    #[test]
+    fn test_apply_schema_override() {
+        let inferred = Schema::new(vec![
+            Field::new("name", DataType::Utf8, true),
+            Field::new("", DataType::Int64, true),
+            Field::new("age", DataType::Int64, true),
+        ]);
+        let schema = Schema::new(vec![
+            Field::new("column_1", DataType::Utf8, true),
+            Field::new("age", DataType::Float64, false),
+        ]);
+
+        let result = apply_schema(inferred, &schema, SchemaMode::Override).unwrap();
+        assert_eq!(result.field(0).data_type(), &DataType::Utf8);
+        assert_eq!(result.field(1).name(), "");
+        assert_eq!(result.field(1).data_type(), &DataType::Utf8);
+        assert_eq!(result.field(2).data_type(), &DataType::Float64);
+        assert!(!result.field(2).is_nullable());
+    }
+
+    #[test]
+    fn test_apply_schema_unknown_column() {
+        let inferred = Schema::new(vec![Field::new("name", DataType::Utf8, true)]);
+        let schema = Schema::new(vec![Field::new("missing", DataType::Utf8, true)]);
+
+        assert!(matches!(
+            apply_schema(inferred, &schema, SchemaMode::Override),
+            Err(Cc2pError::SchemaError(_))
+        ));
+    }
+
+    #[test]
+    fn test_apply_schema_replace() {
+        let inferred = Schema::new(vec![Field::new("name", DataType::Utf8, true)]);
+        let schema = Schema::new(vec![
+            Field::new("first", DataType::Utf8, true),
+            Field::new("second", DataType::Int64, true),
+        ]);
+
+        let result = apply_schema(inferred, &schema, SchemaMode::Replace).unwrap();
+        assert_eq!(result, schema);
+    }
+}
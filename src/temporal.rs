@@ -0,0 +1,128 @@
+use crate::options::ConvertOptions;
+use arrow_array::timezone::Tz;
+use chrono::{DateTime, NaiveDate, NaiveDateTime, SecondsFormat, TimeZone};
+
+/// Parses a value with the custom date formats.
+///
+/// # Returns
+///
+/// Returns the date in ISO 8601 form (`2026-10-16`), or `None` if no format matches.
+pub(crate) fn parse_date(value: &str, options: &ConvertOptions) -> Option<String> {
+    options
+        .date_formats
+        .iter()
+        .find_map(|format| NaiveDate::parse_from_str(value, format).ok())
+        .map(|date| date.format("%Y-%m-%d").to_string())
+}
+
+/// Parses a value with the custom timestamp formats.
+///
+/// Timestamps without an offset are placed in `options.timezone` when one is set.
+///
+/// # Returns
+///
+/// Returns the timestamp in RFC 3339 form, without an offset if it has none, or `None` if no format matches.
+pub(crate) fn parse_timestamp(value: &str, options: &ConvertOptions) -> Option<String> {
+    options.timestamp_formats.iter().find_map(|format| {
+        if let Ok(timestamp) = DateTime::parse_from_str(value, format) {
+            Some(timestamp.to_rfc3339_opts(SecondsFormat::AutoSi, true))
+        } else {
+            NaiveDateTime::parse_from_str(value, format)
+                .ok()
+                .map(|timestamp| localize(timestamp, options))
+        }
+    })
+}
+
+/// Rewrites a value of a timestamp column into a form arrow-csv decodes the same way for any column timezone.
+///
+/// Values matching a custom format are parsed with it. Without a source timezone other values are left to
+/// arrow-csv, otherwise their local time is resolved in the source timezone.
+///
+/// # Returns
+///
+/// Returns the rewritten value, or `None` if the value is kept as it is.
+pub(crate) fn normalize_timestamp(value: &str, options: &ConvertOptions) -> Option<String> {
+    if let Some(timestamp) = parse_timestamp(value, options) {
+        return Some(timestamp);
+    }
+
+    let timezone: Tz = options.timezone.as_deref()?.parse().ok()?;
+    let timestamp = arrow_cast::parse::string_to_datetime(&timezone, value).ok()?;
+    Some(timestamp.fixed_offset().to_rfc3339_opts(SecondsFormat::AutoSi, true))
+}
+
+/// Formats a timestamp without an offset, resolving it in the source timezone when one is set.
+fn localize(timestamp: NaiveDateTime, options: &ConvertOptions) -> String {
+    let local = options
+        .timezone
+        .as_deref()
+        .and_then(|timezone| timezone.parse::<Tz>().ok())
+        .and_then(|timezone| timezone.from_local_datetime(&timestamp).earliest());
+
+    match local {
+        Some(local) => local.fixed_offset().to_rfc3339_opts(SecondsFormat::AutoSi, true),
+        None => timestamp
+            .and_utc()
+            .to_rfc3339_opts(SecondsFormat::AutoSi, true)
+            .trim_end_matches('Z')
+            .to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_date() {
+        let options = ConvertOptions {
+            date_formats: vec!["%d/%m/%Y".to_string(), "%Y%m%d".to_string()],
+            ..ConvertOptions::default()
+        };
+
+        assert_eq!(parse_date("16/10/2026", &options).as_deref(), Some("2026-10-16"));
+        assert_eq!(parse_date("20261016", &options).as_deref(), Some("2026-10-16"));
+        assert_eq!(parse_date("2026-10-16", &options), None);
+    }
+
+    #[test]
+    fn test_parse_timestamp() {
+        let mut options = ConvertOptions {
+            timestamp_formats: vec!["%Y-%m-%d %H:%M:%S %z".to_string(), "%d/%m/%Y %H:%M:%S%.f".to_string()],
+            ..ConvertOptions::default()
+        };
+
+        assert_eq!(
+            parse_timestamp("2026-10-16 12:00:00 +0300", &options).as_deref(),
+            Some("2026-10-16T12:00:00+03:00")
+        );
+        assert_eq!(
+            parse_timestamp("16/10/2026 12:00:00.250", &options).as_deref(),
+            Some("2026-10-16T12:00:00.250")
+        );
+        assert_eq!(parse_timestamp("2026-10-16", &options), None);
+
+        options.timezone = Some("Europe/Istanbul".to_string());
+        assert_eq!(
+            parse_timestamp("16/10/2026 12:00:00", &options).as_deref(),
+            Some("2026-10-16T12:00:00+03:00")
+        );
+    }
+
+    #[test]
+    fn test_normalize_timestamp() {
+        let mut options = ConvertOptions::default();
+        assert_eq!(normalize_timestamp("2026-10-16 12:00:00", &options), None);
+
+        options.timezone = Some("+02:00".to_string());
+        assert_eq!(
+            normalize_timestamp("2026-10-16 12:00:00", &options).as_deref(),
+            Some("2026-10-16T12:00:00+02:00")
+        );
+        assert_eq!(
+            normalize_timestamp("2026-10-16T12:00:00Z", &options).as_deref(),
+            Some("2026-10-16T14:00:00+02:00")
+        );
+    }
+}
@@ -1,5 +1,6 @@
-use crate::conversion::{convert_to_parquet_with_columns, infer_schema, remove_deduplicate_columns};
+use crate::conversion::{convert_to_parquet_with_options, infer_schema_with_options, remove_deduplicate_columns, target_path};
 use crate::error::Result;
+use crate::options::ConvertOptions;
 use crate::utils::find_files;
 use crossterm::{
     event::{self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode, KeyEventKind},
@@ -22,9 +23,7 @@ struct App {
     columns: Vec<(String, String, bool)>, // (name, type, selected)
     column_list_state: ListState,
     active_panel: ActivePanel,
-    delimiter: char,
-    has_header: bool,
-    sampling_size: u16,
+    options: ConvertOptions,
     message: String,
 }
 
@@ -35,7 +34,7 @@ enum ActivePanel {
 }
 
 impl App {
-    fn new(files: Vec<PathBuf>, delimiter: char, has_header: bool, sampling_size: u16) -> App {
+    fn new(files: Vec<PathBuf>, options: ConvertOptions) -> App {
         let mut file_list_state = ListState::default();
         if !files.is_empty() {
             file_list_state.select(Some(0));
@@ -46,9 +45,7 @@ impl App {
             columns: Vec::new(),
             column_list_state: ListState::default(),
             active_panel: ActivePanel::FileList,
-            delimiter,
-            has_header,
-            sampling_size,
+            options,
             message: String::from("Use Arrow keys to navigate, Space to select/unselect, Enter to export, Tab to switch panels, Q to quit"),
         }
     }
@@ -118,23 +115,29 @@ impl App {
     }
 
     fn toggle_column(&mut self) {
-        if let Some(i) = self.column_list_state.selected() {
-            if i < self.columns.len() {
-                self.columns[i].2 = !self.columns[i].2;
-            }
+        if let Some(i) = self.column_list_state.selected()
+            && i < self.columns.len()
+        {
+            self.columns[i].2 = !self.columns[i].2;
         }
     }
 
     fn update_columns(&mut self) {
         if let Some(i) = self.file_list_state.selected() {
             let file_path = &self.files[i];
-            match infer_schema(file_path, self.delimiter, self.has_header, self.sampling_size) {
+            match infer_schema_with_options(file_path, &self.options) {
                 Ok(schema) => {
                     let deduplicated_schema = remove_deduplicate_columns(schema);
                     self.columns = deduplicated_schema
                         .fields()
                         .iter()
-                        .map(|f| (f.name().clone(), f.data_type().to_string(), true))
+                        .map(|f| {
+                            let mut data_type = f.data_type().to_string();
+                            if self.options.column_options.get(f.name()).is_some_and(|c| c.has_bloom_filter()) {
+                                data_type.push_str(" [bloom filter]");
+                            }
+                            (f.name().clone(), data_type, true)
+                        })
                         .collect();
                     if !self.columns.is_empty() {
                         self.column_list_state.select(Some(0));
@@ -167,9 +170,14 @@ impl App {
             }
 
             self.message = format!("Exporting {}...", file_path.display());
-            match convert_to_parquet_with_columns(file_path, self.delimiter, self.has_header, self.sampling_size, selected_cols).await {
+            let options = ConvertOptions {
+                selected_columns: Some(selected_cols),
+                ..self.options.clone()
+            };
+            match convert_to_parquet_with_options(file_path, &options).await {
                 Ok(_) => {
-                    self.message = format!("Successfully exported to {}", file_path.with_extension("parquet").display());
+                    let target = target_path(file_path, &options)?;
+                    self.message = format!("Successfully exported to {}", target.display());
                 }
                 Err(e) => {
                     self.message = format!("Export failed: {}", e);
@@ -180,7 +188,7 @@ impl App {
     }
 }
 
-pub async fn run_tui(path: &str, delimiter: char, has_header: bool, sampling_size: u16) -> Result<()> {
+pub async fn run_tui(path: &str, options: ConvertOptions) -> Result<()> {
     let files = find_files(path).map_err(|e| crate::error::Cc2pError::Other(e.to_string()))?;
     if files.is_empty() {
         return Err(crate::error::Cc2pError::Other(format!("No CSV files found for path: {}", path)));
@@ -192,7 +200,7 @@ pub async fn run_tui(path: &str, delimiter: char, has_header: bool, sampling_siz
     let backend = CrosstermBackend::new(stdout);
     let mut terminal = Terminal::new(backend)?;
 
-    let mut app = App::new(files, delimiter, has_header, sampling_size);
+    let mut app = App::new(files, options);
     app.update_columns();
 
     let res = run_app(&mut terminal, app).await;
@@ -214,43 +222,40 @@ async fn run_app<B: Backend>(terminal: &mut Terminal<B>, mut app: App) -> Result
             .draw(|f| ui(f, &mut app))
             .map_err(|e| crate::error::Cc2pError::Other(e.to_string()))?;
 
-        if event::poll(std::time::Duration::from_millis(100))? {
-            if let Event::Key(key) = event::read()? {
-                if key.kind == KeyEventKind::Press {
-                    match key.code {
-                        KeyCode::Char('q') => return Ok(()),
-                        KeyCode::Down => {
-                            if app.active_panel == ActivePanel::FileList {
-                                app.next_file();
-                            } else {
-                                app.next_column();
-                            }
-                        }
-                        KeyCode::Up => {
-                            if app.active_panel == ActivePanel::FileList {
-                                app.previous_file();
-                            } else {
-                                app.previous_column();
-                            }
-                        }
-                        KeyCode::Tab => {
-                            app.active_panel = if app.active_panel == ActivePanel::FileList {
-                                ActivePanel::ColumnList
-                            } else {
-                                ActivePanel::FileList
-                            };
-                        }
-                        KeyCode::Char(' ') => {
-                            if app.active_panel == ActivePanel::ColumnList {
-                                app.toggle_column();
-                            }
-                        }
-                        KeyCode::Enter => {
-                            let _ = app.export_selected().await;
-                        }
-                        _ => {}
+        if event::poll(std::time::Duration::from_millis(100))?
+            && let Event::Key(key) = event::read()?
+            && key.kind == KeyEventKind::Press
+        {
+            match key.code {
+                KeyCode::Char('q') => return Ok(()),
+                KeyCode::Down => {
+                    if app.active_panel == ActivePanel::FileList {
+                        app.next_file();
+                    } else {
+                        app.next_column();
                     }
                 }
+                KeyCode::Up => {
+                    if app.active_panel == ActivePanel::FileList {
+                        app.previous_file();
+                    } else {
+                        app.previous_column();
+                    }
+                }
+                KeyCode::Tab => {
+                    app.active_panel = if app.active_panel == ActivePanel::FileList {
+                        ActivePanel::ColumnList
+                    } else {
+                        ActivePanel::FileList
+                    };
+                }
+                KeyCode::Char(' ') if app.active_panel == ActivePanel::ColumnList => {
+                    app.toggle_column();
+                }
+                KeyCode::Enter => {
+                    let _ = app.export_selected().await;
+                }
+                _ => {}
             }
         }
     }
@@ -1,8 +1,11 @@
+use crate::archive::{archive_stem, is_archive, is_tar, list_members, split_member};
+use crate::decompress::COMPRESSED_EXTENSIONS;
 use crate::error::{Cc2pError, Result};
 use glob::{MatchOptions, glob_with};
 use once_cell::sync::Lazy;
 use regex::Regex;
-use std::path::PathBuf;
+use std::ffi::OsStr;
+use std::path::{Path, PathBuf};
 
 /// Static regex pattern for cleaning column names
 static COLUMN_NAME_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"[^a-zA-Z0-9_\-\s]").unwrap());
@@ -35,8 +38,20 @@ pub fn clean_column_name(column_name: &str) -> String {
     COLUMN_NAME_REGEX.replace_all(column_name, "").to_string()
 }
 
+/// Path that stands for stdin as input and stdout as output.
+pub const STDIO: &str = "-";
+
+/// Returns `true` if the path is [`STDIO`].
+pub fn is_stdio(path: &Path) -> bool {
+    path.as_os_str() == STDIO
+}
+
 /// Searches for files matching the given pattern.
 ///
+/// Zip and tar archives (`.zip`, `.tar`, `.tgz`, `.tar.gz`, ...) matching the pattern are replaced by their CSV
+/// members, e.g. `drop.zip/sales/jan.csv`, which are read without extracting them. Archives whose members cannot
+/// be listed are reported on stderr and left out, see [`find_inputs`] to keep them.
+///
 /// # Arguments
 ///
 /// * `pattern` - A string slice representing the search pattern.
@@ -63,6 +78,39 @@ pub fn clean_column_name(column_name: &str) -> String {
 /// }
 /// ```
 pub fn find_files(pattern: &str) -> Result<Vec<PathBuf>> {
+    let inputs = find_inputs(pattern)?;
+    Ok(inputs
+        .into_iter()
+        .filter_map(|input| match input {
+            FoundInput::File(path) => Some(path),
+            FoundInput::Unreadable(path, e) => {
+                eprintln!("{}: {}", path.display(), e);
+                None
+            }
+        })
+        .collect())
+}
+
+/// An input found by [`find_inputs`].
+#[derive(Debug)]
+pub enum FoundInput {
+    /// A CSV file or a CSV member of an archive.
+    File(PathBuf),
+    /// An archive whose members cannot be listed, with the error.
+    Unreadable(PathBuf, Cc2pError),
+}
+
+/// Searches for files matching the given pattern, like [`find_files`], but keeps the archives whose members cannot
+/// be listed, e.g. a corrupt zip file, so they can be reported as failed inputs.
+///
+/// # Arguments
+///
+/// * `pattern` - A string slice representing the search pattern.
+///
+/// # Returns
+///
+/// A Result containing the matching files and the archives that cannot be read, in the order they were found.
+pub fn find_inputs(pattern: &str) -> Result<Vec<FoundInput>> {
     let mut files = vec![];
     let options = MatchOptions {
         case_sensitive: false,
@@ -73,12 +121,13 @@ pub fn find_files(pattern: &str) -> Result<Vec<PathBuf>> {
     for entry in glob_with(pattern, options).map_err(|e| Cc2pError::PatternError(e.to_string()))? {
         match entry {
             Ok(p) => {
-                if p.is_file() {
-                    if let Some(ext) = p.extension() {
-                        if ext == "csv" {
-                            files.push(p);
-                        }
+                if p.is_file() && is_archive(&p) {
+                    match list_members(&p) {
+                        Ok(members) => files.extend(members.into_iter().map(|member| FoundInput::File(p.join(member)))),
+                        Err(e) => files.push(FoundInput::Unreadable(p, Cc2pError::FileError(e))),
                     }
+                } else if p.is_file() && is_csv_file(&p) {
+                    files.push(FoundInput::File(p));
                 }
             }
             Err(e) => {
@@ -91,33 +140,237 @@ pub fn find_files(pattern: &str) -> Result<Vec<PathBuf>> {
     Ok(files)
 }
 
-/// Deletes a file if it exists.
+/// Returns the tar archive a CSV file is a member of, e.g. `drop.tar.gz` for `drop.tar.gz/sales/jan.csv`.
+///
+/// A tar archive can only be read from the start, so its members are best converted together, see
+/// [`convert_tar_members`](crate::conversion::convert_tar_members).
+pub fn tar_archive(path: &Path) -> Option<&Path> {
+    split_member(path).map(|(archive, _)| archive).filter(|archive| is_tar(archive))
+}
+
+/// Returns `true` if the path has the extension `csv`, optionally followed by a compression extension
+/// (`gz`, `zst`, `bz2` or `xz`), e.g. `data.csv` or `data.csv.gz`. Extensions are matched case-insensitively.
+pub(crate) fn is_csv_file(path: &Path) -> bool {
+    match path.extension() {
+        Some(ext) if ext.eq_ignore_ascii_case("csv") => true,
+        Some(ext) if is_compressed_extension(ext) => path.with_extension("").extension().is_some_and(|e| e.eq_ignore_ascii_case("csv")),
+        _ => false,
+    }
+}
+
+/// Returns `true` if a file extension is one of [`COMPRESSED_EXTENSIONS`], in any case.
+fn is_compressed_extension(extension: &OsStr) -> bool {
+    COMPRESSED_EXTENSIONS.iter().any(|c| extension.eq_ignore_ascii_case(c))
+}
+
+/// Returns the path of a file written next to a CSV file, replacing the `csv` and compression extensions.
+///
+/// Members of an archive are written to a directory named after the archive, e.g. `drop/sales.parquet` for
+/// `drop.zip/sales.csv`.
 ///
 /// # Arguments
 ///
-/// * `filename` - The name of the file to delete.
+/// * `file_path` - The path of the CSV file.
+/// * `extension` - The extension of the new file, e.g. `parquet`.
+///
+/// # Examples
+///
+/// ```rust
+/// use std::path::{Path, PathBuf};
+/// use cc2p::utils::output_path;
+///
+/// assert_eq!(output_path(Path::new("data/sales.csv"), "parquet"), PathBuf::from("data/sales.parquet"));
+/// assert_eq!(output_path(Path::new("data/sales.csv.gz"), "parquet"), PathBuf::from("data/sales.parquet"));
+/// ```
+pub fn output_path(file_path: &Path, extension: &str) -> PathBuf {
+    let file_path = &match split_member(file_path) {
+        Some((archive, member)) => archive_stem(archive).join(member),
+        None => file_path.to_path_buf(),
+    };
+    let is_compressed = file_path.extension().is_some_and(is_compressed_extension);
+    if is_compressed {
+        file_path.with_extension("").with_extension(extension)
+    } else {
+        file_path.with_extension(extension)
+    }
+}
+
+/// Returns the directory a glob pattern searches in, i.e. its leading components without wildcards.
+///
+/// # Examples
+///
+/// ```rust
+/// use std::path::PathBuf;
+/// use cc2p::utils::glob_base;
+///
+/// assert_eq!(glob_base("data/**/*.csv"), PathBuf::from("data"));
+/// assert_eq!(glob_base("data/2026-*/sales.csv"), PathBuf::from("data"));
+/// assert_eq!(glob_base("*.csv"), PathBuf::from(""));
+/// ```
+pub fn glob_base(pattern: &str) -> PathBuf {
+    let path = Path::new(pattern);
+    let mut base = PathBuf::new();
+    for component in path.parent().into_iter().flat_map(Path::components) {
+        if component.as_os_str().to_string_lossy().contains(['*', '?', '[']) {
+            break;
+        }
+        base.push(component);
+    }
+    base
+}
+
+/// Replaces the `{name}` placeholders of a template with their values.
+///
+/// # Arguments
+///
+/// * `template` - The template, e.g. `{stem}_{date}.parquet`.
+/// * `values` - The names of the placeholders and their values.
 ///
 /// # Returns
 ///
-/// Returns `Ok(())` if the file was deleted or didn't exist, otherwise returns an error.
+/// Returns the rendered template, or a `ConfigError` if it contains an unknown or unclosed placeholder.
 ///
 /// # Examples
 ///
 /// ```rust
-/// use cc2p::utils::delete_if_exist;
+/// use cc2p::utils::render_template;
 ///
-/// #[tokio::main]
-/// async fn main() -> cc2p::error::Result<()> {
-///     delete_if_exist("temp_file.txt").await?;
-///     Ok(())
-/// }
+/// let values = [("stem", "sales"), ("date", "2026-10-17")];
+/// assert_eq!(render_template("{stem}_{date}.parquet", &values).unwrap(), "sales_2026-10-17.parquet");
+/// assert!(render_template("{name}.parquet", &values).is_err());
 /// ```
-pub async fn delete_if_exist(filename: &str) -> Result<()> {
-    if tokio::fs::metadata(filename).await.is_ok() {
-        tokio::fs::remove_file(filename).await?;
+pub fn render_template(template: &str, values: &[(&str, &str)]) -> Result<String> {
+    let mut rendered = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        rendered.push_str(&rest[..start]);
+        let end = rest[start..]
+            .find('}')
+            .ok_or_else(|| Cc2pError::ConfigError(format!("unclosed placeholder in template '{}'", template)))?;
+        let name = &rest[start + 1..start + end];
+        let value = values
+            .iter()
+            .find(|(n, _)| *n == name)
+            .ok_or_else(|| Cc2pError::ConfigError(format!("unknown placeholder '{{{}}}' in template '{}'", name, template)))?;
+        rendered.push_str(value.1);
+        rest = &rest[start + end + 1..];
     }
+    rendered.push_str(rest);
+    Ok(rendered)
+}
 
-    Ok(())
+/// Parses a human readable byte size such as `128MB`, `64KiB` or `4096`.
+///
+/// Units are case-insensitive and use powers of 1024 (`K`, `M`, `G`, optionally followed by `B` or `iB`).
+///
+/// # Arguments
+///
+/// * `value` - The size to parse.
+///
+/// # Examples
+///
+/// ```rust
+/// use cc2p::utils::parse_byte_size;
+///
+/// assert_eq!(parse_byte_size("4096").unwrap(), 4096);
+/// assert_eq!(parse_byte_size("128MB").unwrap(), 128 * 1024 * 1024);
+/// assert!(parse_byte_size("12XB").is_err());
+/// ```
+///
+/// # Returns
+///
+/// Returns the number of bytes, or a `ConfigError` if the value cannot be parsed.
+pub fn parse_byte_size(value: &str) -> Result<usize> {
+    let value = value.trim();
+    let split = value.find(|c: char| !c.is_ascii_digit()).unwrap_or(value.len());
+    let (number, unit) = value.split_at(split);
+    let number: usize = number
+        .parse()
+        .map_err(|_| Cc2pError::ConfigError(format!("invalid byte size '{}'", value)))?;
+
+    let multiplier = match unit.trim().to_ascii_lowercase().as_str() {
+        "" | "b" => 1,
+        "k" | "kb" | "kib" => 1024,
+        "m" | "mb" | "mib" => 1024 * 1024,
+        "g" | "gb" | "gib" => 1024 * 1024 * 1024,
+        _ => return Err(Cc2pError::ConfigError(format!("invalid byte size unit in '{}'", value))),
+    };
+
+    number
+        .checked_mul(multiplier)
+        .ok_or_else(|| Cc2pError::ConfigError(format!("byte size '{}' is too large", value)))
+}
+
+/// Formats a number of bytes in the units accepted by [`parse_byte_size`], e.g. `1.5 MB`.
+///
+/// # Arguments
+///
+/// * `bytes` - The number of bytes.
+///
+/// # Examples
+///
+/// ```rust
+/// use cc2p::utils::format_byte_size;
+///
+/// assert_eq!(format_byte_size(512), "512 B");
+/// assert_eq!(format_byte_size(1536 * 1024), "1.5 MB");
+/// ```
+pub fn format_byte_size(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["KB", "MB", "GB", "TB"];
+
+    if bytes < 1024 {
+        return format!("{} B", bytes);
+    }
+    let mut size = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    format!("{:.1} {}", size, UNITS[unit])
+}
+
+/// Parses a delimiter given on the command line.
+///
+/// Besides literal delimiters such as `;`, `§` or `||`, the names `tab`, `comma`, `semicolon`, `pipe` and `space`
+/// and the escapes `\t` and `\xHH` are accepted.
+///
+/// # Arguments
+///
+/// * `value` - The delimiter to parse.
+///
+/// # Examples
+///
+/// ```rust
+/// use cc2p::utils::parse_delimiter;
+///
+/// assert_eq!(parse_delimiter("tab").unwrap(), "\t");
+/// assert_eq!(parse_delimiter("\\x1f").unwrap(), "\u{1f}");
+/// assert_eq!(parse_delimiter("||").unwrap(), "||");
+/// assert!(parse_delimiter("").is_err());
+/// ```
+///
+/// # Returns
+///
+/// Returns the delimiter, or a `ConfigError` if the value is empty or an invalid escape.
+pub fn parse_delimiter(value: &str) -> Result<String> {
+    let delimiter = match value.to_ascii_lowercase().as_str() {
+        "" => return Err(Cc2pError::ConfigError("the delimiter cannot be empty".to_string())),
+        "tab" | "\\t" => "\t".to_string(),
+        "comma" => ",".to_string(),
+        "semicolon" => ";".to_string(),
+        "pipe" => "|".to_string(),
+        "space" => " ".to_string(),
+        escape if escape.starts_with("\\x") => {
+            let byte = u8::from_str_radix(&escape[2..], 16)
+                .ok()
+                .filter(u8::is_ascii)
+                .ok_or_else(|| Cc2pError::ConfigError(format!("invalid delimiter escape '{}', expected \\x00 to \\x7f", value)))?;
+            char::from(byte).to_string()
+        }
+        _ => value.to_string(),
+    };
+    Ok(delimiter)
 }
 
 #[cfg(test)]
@@ -147,25 +400,87 @@ mod tests {
         assert_eq!(find_files("testdata/*delimi*.csv").unwrap().len(), 1);
     }
 
-    #[tokio::test]
-    async fn test_delete_if_exist() {
-        // Create a temporary file
-        let temp_file = "testdata/temp_test_file.txt";
-        fs::write(temp_file, "test content").await.unwrap();
+    #[test]
+    fn test_find_inputs_with_broken_archive() {
+        let dir = std::env::temp_dir().join("cc2p_broken_archive_test");
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("broken.zip"), "not a zip file").unwrap();
+        std::fs::write(dir.join("data.csv"), "id\n1\n").unwrap();
+
+        let pattern = dir.join("*").to_string_lossy().into_owned();
+        let inputs = find_inputs(&pattern).unwrap();
+        assert_eq!(inputs.len(), 2);
+        assert!(
+            inputs
+                .iter()
+                .any(|input| matches!(input, FoundInput::Unreadable(path, Cc2pError::FileError(_)) if path.ends_with("broken.zip")))
+        );
+
+        // find_files only keeps the files it can convert
+        assert_eq!(find_files(&pattern).unwrap(), vec![dir.join("data.csv")]);
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn test_compressed_file_names() {
+        assert!(is_csv_file(Path::new("data.csv")));
+        assert!(is_csv_file(Path::new("data.csv.gz")));
+        assert!(is_csv_file(Path::new("data.csv.zst")));
+        assert!(!is_csv_file(Path::new("data.gz")));
+        assert!(!is_csv_file(Path::new("data.csv.zip")));
+        assert!(is_csv_file(Path::new("DATA.CSV")));
+        assert!(is_csv_file(Path::new("Data.Csv.GZ")));
 
-        // Verify file exists
-        assert!(fs::metadata(temp_file).await.is_ok());
+        assert_eq!(output_path(Path::new("data.csv.bz2"), "parquet"), PathBuf::from("data.parquet"));
+        assert_eq!(
+            output_path(Path::new("data.v2.csv.xz"), "schema.json"),
+            PathBuf::from("data.v2.schema.json")
+        );
+        assert_eq!(output_path(Path::new("data.v2.csv"), "parquet"), PathBuf::from("data.v2.parquet"));
+        assert_eq!(output_path(Path::new("DATA.CSV.GZ"), "parquet"), PathBuf::from("DATA.parquet"));
+    }
 
-        // Delete the file
-        let result = delete_if_exist(temp_file).await;
-        assert!(result.is_ok());
+    #[test]
+    fn test_output_naming() {
+        assert_eq!(glob_base("/srv/drops/*.zip"), PathBuf::from("/srv/drops"));
+        assert_eq!(glob_base("data/sales.csv"), PathBuf::from("data"));
+        assert_eq!(glob_base("data/[ab]/*.csv"), PathBuf::from("data"));
 
-        // Verify file no longer exists
-        assert!(fs::metadata(temp_file).await.is_err());
+        let values = [("stem", "jan"), ("parent", "2026")];
+        assert_eq!(
+            render_template("{parent}/{stem}.snappy.parquet", &values).unwrap(),
+            "2026/jan.snappy.parquet"
+        );
+        assert_eq!(render_template("out.parquet", &values).unwrap(), "out.parquet");
+        assert!(matches!(render_template("{stem.parquet", &values), Err(Cc2pError::ConfigError(_))));
+    }
 
-        // Test deleting a non-existent file (should not error)
-        let result = delete_if_exist("testdata/non_existent_file.txt").await;
-        assert!(result.is_ok());
+    #[test]
+    fn test_parse_byte_size() {
+        assert_eq!(parse_byte_size("0").unwrap(), 0);
+        assert_eq!(parse_byte_size("512b").unwrap(), 512);
+        assert_eq!(parse_byte_size("64K").unwrap(), 64 * 1024);
+        assert_eq!(parse_byte_size("64KiB").unwrap(), 64 * 1024);
+        assert_eq!(parse_byte_size("128MB").unwrap(), 128 * 1024 * 1024);
+        assert_eq!(parse_byte_size("1 GB").unwrap(), 1024 * 1024 * 1024);
+        assert!(parse_byte_size("").is_err());
+        assert!(parse_byte_size("MB").is_err());
+        assert!(parse_byte_size("1.5MB").is_err());
+        assert!(parse_byte_size("10TB").is_err());
+    }
+
+    #[test]
+    fn test_parse_delimiter() {
+        assert_eq!(parse_delimiter(",").unwrap(), ",");
+        assert_eq!(parse_delimiter("TAB").unwrap(), "\t");
+        assert_eq!(parse_delimiter("\\t").unwrap(), "\t");
+        assert_eq!(parse_delimiter("semicolon").unwrap(), ";");
+        assert_eq!(parse_delimiter("§").unwrap(), "§");
+        assert_eq!(parse_delimiter("\\x09").unwrap(), "\t");
+        assert!(parse_delimiter("\\xzz").is_err());
+        assert!(parse_delimiter("\\xff").is_err());
     }
 
     #[tokio::test]
@@ -181,7 +496,7 @@ mod tests {
         }
 
         // Clean up
-        let _ = delete_if_exist(temp_file).await;
+        let _ = fs::remove_file(temp_file).await;
     }
 
     #[test]